@@ -1,6 +1,6 @@
 use node_template_runtime::{
-	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, Signature, SudoConfig,
-	SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, KittiesModuleConfig,
+	OcwModuleConfig, Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
@@ -36,6 +36,24 @@ pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
 	(get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
 }
 
+/// A small starter collection of kitties owned by the given accounts, used by the dev chain.
+pub fn starter_kitties(owners: &[AccountId]) -> Vec<(AccountId, [u8; 16])> {
+	owners
+		.iter()
+		.enumerate()
+		.flat_map(|(i, owner)| {
+			(0..2u8).map(move |j| {
+				let mut dna = [0u8; 16];
+				for (k, gene) in dna.iter_mut().enumerate() {
+					*gene = (i as u8).wrapping_mul(97) ^
+						j.wrapping_mul(53) ^ (k as u8).wrapping_mul(31);
+				}
+				(owner.clone(), dna)
+			})
+		})
+		.collect()
+}
+
 pub fn development_config() -> Result<ChainSpec, String> {
 	let wasm_binary = WASM_BINARY.ok_or_else(|| "Development wasm not available".to_string())?;
 
@@ -59,6 +77,13 @@ pub fn development_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
 				],
+				// Starter kitties
+				starter_kitties(&[
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+				]),
+				// Oracle and kitty asset authorities, Alice's `demo` and `kitt` keys are inserted by the dev node
+				vec![get_account_id_from_seed::<sr25519::Public>("Alice")],
 				true,
 			)
 		},
@@ -107,6 +132,13 @@ pub fn local_testnet_config() -> Result<ChainSpec, String> {
 					get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
 					get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
 				],
+				// Starter kitties
+				vec![],
+				// Oracle authorities
+				vec![
+					get_account_id_from_seed::<sr25519::Public>("Alice"),
+					get_account_id_from_seed::<sr25519::Public>("Bob"),
+				],
 				true,
 			)
 		},
@@ -130,6 +162,8 @@ fn testnet_genesis(
 	initial_authorities: Vec<(AuraId, GrandpaId)>,
 	root_key: AccountId,
 	endowed_accounts: Vec<AccountId>,
+	kitties: Vec<(AccountId, [u8; 16])>,
+	oracle_authorities: Vec<AccountId>,
 	_enable_println: bool,
 ) -> GenesisConfig {
 	GenesisConfig {
@@ -152,5 +186,12 @@ fn testnet_genesis(
 			key: Some(root_key),
 		},
 		transaction_payment: Default::default(),
+		assets: Default::default(),
+		// The oracle authorities' offchain workers also submit kitty asset scores
+		kitties_module: KittiesModuleConfig {
+			kitties,
+			asset_authorities: oracle_authorities.clone(),
+		},
+		ocw_module: OcwModuleConfig { oracle_authorities, ..Default::default() },
 	}
 }
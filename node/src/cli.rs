@@ -7,6 +7,34 @@ pub struct Cli {
 
 	#[clap(flatten)]
 	pub run: RunCmd,
+
+	/// Override the URL the offchain worker uses for an endpoint, e.g.
+	/// `--ocw-endpoint coincap=http://localhost:8080/dot`. Endpoint names are `github` and the
+	/// names of the price sources. Can be repeated.
+	#[clap(long = "ocw-endpoint", value_name = "NAME=URL", parse(try_from_str = parse_endpoint))]
+	pub ocw_endpoints: Vec<(String, String)>,
+
+	/// Add a header, such as an API key, to the offchain worker requests for an endpoint, e.g.
+	/// `--ocw-header coincap:Authorization=Bearer <key>`. Can be repeated.
+	#[clap(long = "ocw-header", value_name = "NAME:HEADER=VALUE", parse(try_from_str = parse_header))]
+	pub ocw_headers: Vec<(String, (String, String))>,
+}
+
+fn parse_endpoint(s: &str) -> Result<(String, String), String> {
+	match s.split_once('=') {
+		Some((name, url)) if !name.is_empty() => Ok((name.into(), url.into())),
+		_ => Err(format!("expected NAME=URL, got `{}`", s)),
+	}
+}
+
+fn parse_header(s: &str) -> Result<(String, (String, String)), String> {
+	let (name, header) = s
+		.split_once(':')
+		.filter(|(name, _)| !name.is_empty())
+		.ok_or_else(|| format!("expected NAME:HEADER=VALUE, got `{}`", s))?;
+	let (header, value) =
+		parse_endpoint(header).map_err(|_| format!("expected NAME:HEADER=VALUE, got `{}`", s))?;
+	Ok((name.into(), (header, value)))
 }
 
 #[derive(Debug, clap::Subcommand)]
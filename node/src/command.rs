@@ -185,8 +185,9 @@ pub fn run() -> sc_cli::Result<()> {
 		},
 		None => {
 			let runner = cli.create_runner(&cli.run)?;
+			let ocw_endpoints = service::ocw_endpoints(&cli.ocw_endpoints, &cli.ocw_headers);
 			runner.run_node_until_exit(|config| async move {
-				service::new_full(config).map_err(sc_cli::Error::Service)
+				service::new_full(config, ocw_endpoints).map_err(sc_cli::Error::Service)
 			})
 		},
 	}
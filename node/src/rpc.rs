@@ -7,28 +7,42 @@
 
 use std::sync::Arc;
 
-use jsonrpsee::RpcModule;
-use node_template_runtime::{opaque::Block, AccountId, Balance, Index};
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::{CallError, ErrorObject},
+	RpcModule,
+};
+use node_template_runtime::{
+	opaque::Block, pallet_ocw::endpoint, AccountId, AssetId, Balance, Index, KittyIndex,
+};
+use pallet_kitties_runtime_api::{KittiesApi as KittiesRuntimeApi, KittyInfo};
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
+use sp_core::offchain::{OffchainStorage, STORAGE_PREFIX};
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+
+use crate::service::set_ocw_endpoint;
 
 pub use sc_rpc_api::DenyUnsafe;
 
 /// Full client dependencies.
-pub struct FullDeps<C, P> {
+pub struct FullDeps<C, P, S> {
 	/// The client instance to use.
 	pub client: Arc<C>,
 	/// Transaction pool instance.
 	pub pool: Arc<P>,
+	/// Offchain storage, where the offchain worker endpoints are configured.
+	pub offchain_storage: Option<S>,
 	/// Whether to deny unsafe calls
 	pub deny_unsafe: DenyUnsafe,
 }
 
 /// Instantiate all full RPC extensions.
-pub fn create_full<C, P>(
-	deps: FullDeps<C, P>,
+pub fn create_full<C, P, S>(
+	deps: FullDeps<C, P, S>,
 ) -> Result<RpcModule<()>, Box<dyn std::error::Error + Send + Sync>>
 where
 	C: ProvideRuntimeApi<Block>,
@@ -37,16 +51,22 @@ where
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Index>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BlockBuilder<Block>,
+	C::Api: KittiesRuntimeApi<Block, AccountId, KittyIndex, Balance, AssetId>,
 	P: TransactionPool + 'static,
+	S: OffchainStorage + 'static,
 {
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 
 	let mut module = RpcModule::new(());
-	let FullDeps { client, pool, deny_unsafe } = deps;
+	let FullDeps { client, pool, offchain_storage, deny_unsafe } = deps;
 
 	module.merge(System::new(client.clone(), pool.clone(), deny_unsafe).into_rpc())?;
-	module.merge(TransactionPayment::new(client).into_rpc())?;
+	module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	module.merge(Kitties::new(client).into_rpc())?;
+	if let Some(storage) = offchain_storage {
+		module.merge(OcwEndpoints::new(storage, deny_unsafe).into_rpc())?;
+	}
 
 	// Extend this RPC with a custom API by using the following syntax.
 	// `YourRpcStruct` should have a reference to a client, which is needed
@@ -55,3 +75,186 @@ where
 
 	Ok(module)
 }
+
+/// Kitty queries exposed by the kitties runtime API.
+#[rpc(client, server)]
+pub trait KittiesApi<BlockHash> {
+	/// Full information of a kitty.
+	#[method(name = "kitties_kitty")]
+	fn kitty(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<KittyInfo<AccountId, Balance, AssetId>>>;
+
+	/// One page of kitties listed for sale together with their prices and the asset each
+	/// price is quoted in (`None` for the native currency).
+	#[method(name = "kitties_kittiesForSale")]
+	fn kitties_for_sale(
+		&self,
+		page: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<(KittyIndex, Balance, Option<AssetId>)>>;
+
+	/// Sale price of a kitty and the asset it is quoted in, if it is listed.
+	#[method(name = "kitties_priceOf")]
+	fn price_of(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<BlockHash>,
+	) -> RpcResult<Option<(Balance, Option<AssetId>)>>;
+
+	/// Ancestors of a kitty, generation by generation, up to `depth` generations.
+	#[method(name = "kitties_ancestors")]
+	fn ancestors(
+		&self,
+		kitty_id: KittyIndex,
+		depth: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<KittyIndex>>;
+
+	/// One page of the descendants of a kitty, generation by generation.
+	#[method(name = "kitties_descendants")]
+	fn descendants(
+		&self,
+		kitty_id: KittyIndex,
+		page: u32,
+		at: Option<BlockHash>,
+	) -> RpcResult<Vec<KittyIndex>>;
+}
+
+/// Implements the [`KittiesApiServer`] RPC trait on top of the kitties runtime API.
+pub struct Kitties<C> {
+	client: Arc<C>,
+}
+
+impl<C> Kitties<C> {
+	/// Creates a new instance of the kitties RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client }
+	}
+}
+
+/// Maps a runtime API error into an RPC error.
+fn runtime_error(e: impl std::fmt::Debug) -> jsonrpsee::core::Error {
+	CallError::Custom(ErrorObject::owned(1, "Runtime error", Some(format!("{:?}", e)))).into()
+}
+
+#[async_trait]
+impl<C> KittiesApiServer<<Block as BlockT>::Hash> for Kitties<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: KittiesRuntimeApi<Block, AccountId, KittyIndex, Balance, AssetId>,
+{
+	fn kitty(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<KittyInfo<AccountId, Balance, AssetId>>> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		self.client.runtime_api().kitty(&at, kitty_id).map_err(runtime_error)
+	}
+
+	fn kitties_for_sale(
+		&self,
+		page: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<(KittyIndex, Balance, Option<AssetId>)>> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		self.client.runtime_api().kitties_for_sale(&at, page).map_err(runtime_error)
+	}
+
+	fn price_of(
+		&self,
+		kitty_id: KittyIndex,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Option<(Balance, Option<AssetId>)>> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		self.client.runtime_api().price_of(&at, kitty_id).map_err(runtime_error)
+	}
+
+	fn ancestors(
+		&self,
+		kitty_id: KittyIndex,
+		depth: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<KittyIndex>> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		self.client.runtime_api().ancestors(&at, kitty_id, depth).map_err(runtime_error)
+	}
+
+	fn descendants(
+		&self,
+		kitty_id: KittyIndex,
+		page: u32,
+		at: Option<<Block as BlockT>::Hash>,
+	) -> RpcResult<Vec<KittyIndex>> {
+		let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+		self.client
+			.runtime_api()
+			.descendants(&at, kitty_id, page)
+			.map_err(runtime_error)
+	}
+}
+
+/// Per-node configuration of the HTTP endpoints used by the offchain worker.
+#[rpc(client, server)]
+pub trait OcwEndpointApi {
+	/// Sets the URL and extra headers, such as API keys, of offchain worker endpoint `name`.
+	/// An empty `url` keeps the built-in URL.
+	#[method(name = "ocw_setEndpoint")]
+	fn set_endpoint(
+		&self,
+		name: String,
+		url: String,
+		headers: Vec<(String, String)>,
+	) -> RpcResult<()>;
+
+	/// Removes the configuration of endpoint `name`, so the built-in URL is used again.
+	#[method(name = "ocw_removeEndpoint")]
+	fn remove_endpoint(&self, name: String) -> RpcResult<()>;
+}
+
+/// Implements the [`OcwEndpointApiServer`] RPC trait on top of the offchain storage.
+///
+/// Both methods are unsafe, since the headers may contain secrets.
+pub struct OcwEndpoints<S> {
+	storage: S,
+	deny_unsafe: DenyUnsafe,
+}
+
+impl<S> OcwEndpoints<S> {
+	/// Creates a new instance of the offchain worker endpoint RPC handler.
+	pub fn new(storage: S, deny_unsafe: DenyUnsafe) -> Self {
+		Self { storage, deny_unsafe }
+	}
+}
+
+impl<S: OffchainStorage + 'static> OcwEndpointApiServer for OcwEndpoints<S> {
+	fn set_endpoint(
+		&self,
+		name: String,
+		url: String,
+		headers: Vec<(String, String)>,
+	) -> RpcResult<()> {
+		self.deny_unsafe.check_if_safe()?;
+		let endpoint = endpoint::Endpoint {
+			url: url.into_bytes(),
+			headers: headers
+				.into_iter()
+				.map(|(header, value)| (header.into_bytes(), value.into_bytes()))
+				.collect(),
+		};
+		// Clones of the offchain storage share the same database.
+		set_ocw_endpoint(&mut self.storage.clone(), &name, &endpoint);
+		Ok(())
+	}
+
+	fn remove_endpoint(&self, name: String) -> RpcResult<()> {
+		self.deny_unsafe.check_if_safe()?;
+		self.storage
+			.clone()
+			.remove(STORAGE_PREFIX, &endpoint::storage_key(name.as_bytes()));
+		Ok(())
+	}
+}
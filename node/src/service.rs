@@ -1,7 +1,9 @@
 //! Service and ServiceFactory implementation. Specialized wrapper over substrate service.
 
-use node_template_runtime::{self, opaque::Block, RuntimeApi};
-use sc_client_api::{BlockBackend, ExecutorProvider};
+use codec::Encode;
+use node_template_runtime::{self, opaque::Block, pallet_kitties, pallet_ocw, RuntimeApi};
+use pallet_ocw::{endpoint, Endpoint};
+use sc_client_api::{Backend, BlockBackend, ExecutorProvider};
 use sc_consensus_aura::{ImportQueueParams, SlotProportion, StartAuraParams};
 pub use sc_executor::NativeElseWasmExecutor;
 use sc_finality_grandpa::SharedVoterState;
@@ -9,7 +11,9 @@ use sc_keystore::LocalKeystore;
 use sc_service::{error::Error as ServiceError, Configuration, TaskManager};
 use sc_telemetry::{Telemetry, TelemetryWorker};
 use sp_consensus_aura::sr25519::AuthorityPair as AuraPair;
-use std::{sync::Arc, time::Duration};
+use sp_core::offchain::{OffchainStorage, STORAGE_PREFIX};
+use sp_keystore::SyncCryptoStore;
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
 
 // Our native executor instance.
 pub struct ExecutorDispatch;
@@ -156,8 +160,38 @@ fn remote_keystore(_url: &String) -> Result<Arc<LocalKeystore>, &'static str> {
 	Err("Remote Keystore not supported.")
 }
 
+/// Collects the `--ocw-endpoint` and `--ocw-header` flags into one endpoint per name.
+///
+/// An endpoint with headers only keeps the built-in URL.
+pub fn ocw_endpoints(
+	urls: &[(String, String)],
+	headers: &[(String, (String, String))],
+) -> BTreeMap<String, Endpoint> {
+	let mut endpoints = BTreeMap::<String, Endpoint>::new();
+	for (name, url) in urls {
+		endpoints.entry(name.clone()).or_default().url = url.clone().into_bytes();
+	}
+	for (name, (header, value)) in headers {
+		endpoints
+			.entry(name.clone())
+			.or_default()
+			.headers
+			.push((header.clone().into_bytes(), value.clone().into_bytes()));
+	}
+	endpoints
+}
+
+/// Writes the configuration of offchain worker endpoint `name` into the persistent offchain
+/// storage, where the worker reads it before every request.
+pub fn set_ocw_endpoint<S: OffchainStorage>(storage: &mut S, name: &str, endpoint: &Endpoint) {
+	storage.set(STORAGE_PREFIX, &endpoint::storage_key(name.as_bytes()), &endpoint.encode());
+}
+
 /// Builds a new service for a full client.
-pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError> {
+pub fn new_full(
+	mut config: Configuration,
+	ocw_endpoints: BTreeMap<String, Endpoint>,
+) -> Result<TaskManager, ServiceError> {
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -207,6 +241,22 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 		})?;
 
 	if config.offchain_worker.enabled {
+		// On dev chains, insert Alice's offchain worker keys so `author_insertKey` isn't needed.
+		if config.chain_spec.id() == "dev" {
+			let keystore = keystore_container.sync_keystore();
+			for key_type in [pallet_ocw::KEY_TYPE, pallet_kitties::KEY_TYPE] {
+				SyncCryptoStore::sr25519_generate_new(&*keystore, key_type, Some("//Alice"))
+					.map_err(|e| ServiceError::Other(format!("Failed to insert dev key: {}", e)))?;
+			}
+		}
+
+		// Endpoints given on the command line override those stored by earlier runs or the RPC.
+		if let Some(mut storage) = backend.offchain_storage() {
+			for (name, endpoint) in &ocw_endpoints {
+				set_ocw_endpoint(&mut storage, name, endpoint);
+			}
+		}
+
 		sc_service::build_offchain_workers(
 			&config,
 			task_manager.spawn_handle(),
@@ -225,10 +275,15 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
 	let rpc_extensions_builder = {
 		let client = client.clone();
 		let pool = transaction_pool.clone();
+		let offchain_storage = backend.offchain_storage();
 
 		Box::new(move |deny_unsafe, _| {
-			let deps =
-				crate::rpc::FullDeps { client: client.clone(), pool: pool.clone(), deny_unsafe };
+			let deps = crate::rpc::FullDeps {
+				client: client.clone(),
+				pool: pool.clone(),
+				offchain_storage: offchain_storage.clone(),
+				deny_unsafe,
+			};
 			crate::rpc::create_full(deps).map_err(Into::into)
 		})
 	};
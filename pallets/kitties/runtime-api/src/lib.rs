@@ -0,0 +1,29 @@
+//! kitties pallet 的 Runtime API，供节点 RPC 查询kitty信息、市场和族谱
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+use sp_std::vec::Vec;
+
+pub use pallet_kitties::KittyInfo;
+
+sp_api::decl_runtime_apis! {
+	pub trait KittiesApi<AccountId, KittyIndex, Balance, AssetId>
+	where
+		AccountId: Codec,
+		KittyIndex: Codec,
+		Balance: Codec,
+		AssetId: Codec,
+	{
+		/// 查询kitty的完整信息，包括售价使用的资产
+		fn kitty(kitty_id: KittyIndex) -> Option<KittyInfo<AccountId, Balance, AssetId>>;
+		/// 分页查询正在出售的kitty、价格及计价资产，资产为 `None` 表示以原生代币计价
+		fn kitties_for_sale(page: u32) -> Vec<(KittyIndex, Balance, Option<AssetId>)>;
+		/// 查询kitty的售价及计价资产
+		fn price_of(kitty_id: KittyIndex) -> Option<(Balance, Option<AssetId>)>;
+		/// 按代查询kitty的祖先，深度有上限
+		fn ancestors(kitty_id: KittyIndex, depth: u32) -> Vec<KittyIndex>;
+		/// 按代分页查询kitty的后代
+		fn descendants(kitty_id: KittyIndex, page: u32) -> Vec<KittyIndex>;
+	}
+}
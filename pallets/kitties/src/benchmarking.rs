@@ -0,0 +1,622 @@
+use crate::*;
+use codec::Decode;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::traits::{fungibles, Currency, EnsureOrigin};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Hash, IdentifyAccount, TrailingZeroInput};
+use sp_std::vec;
+
+const SEED: u32 = 0;
+
+fn assert_last_event<T: Config>(generic_event: <T as Config>::Event) {
+	frame_system::Pallet::<T>::assert_last_event(generic_event.into());
+}
+
+// 给账户充值，足够支付多次质押和购买
+fn fund_account<T: Config>(who: &T::AccountId) {
+	T::Currency::make_free_balance_be(who, T::KittyStake::get() * 1_000u32.into());
+}
+
+fn funded_account<T: Config>(name: &'static str, index: u32) -> T::AccountId {
+	let who: T::AccountId = account(name, index, SEED);
+	fund_account::<T>(&who);
+	who
+}
+
+// 为账户创建一只kitty，返回kitty id
+fn create_kitty<T: Config>(owner: &T::AccountId) -> T::KittyIndex {
+	let kitty_id = Pallet::<T>::next_kitty_id();
+	Pallet::<T>::create(RawOrigin::Signed(owner.clone()).into()).expect("kitty created");
+	kitty_id
+}
+
+// 甲乙双方各创建一只kitty，由甲方发起交换kitty的交易
+fn open_kitty_swap<T: Config>(
+	maker: &T::AccountId,
+	taker: &T::AccountId,
+) -> (TradeId, T::KittyIndex, T::KittyIndex) {
+	let kitty_id = create_kitty::<T>(maker);
+	let other_id = create_kitty::<T>(taker);
+	let trade_id = Pallet::<T>::next_trade_id();
+	let deadline = frame_system::Pallet::<T>::block_number() + 10u32.into();
+	Pallet::<T>::open_trade(
+		RawOrigin::Signed(maker.clone()).into(),
+		kitty_id,
+		taker.clone(),
+		TradeConsideration::Kitty(other_id),
+		deadline,
+	)
+	.expect("trade opened");
+	(trade_id, kitty_id, other_id)
+}
+
+// 创建一只kitty并拆分为 100 份，保留价为一次质押的金额
+fn fractionalized_kitty<T: Config>(owner: &T::AccountId) -> T::KittyIndex {
+	let kitty_id = create_kitty::<T>(owner);
+	Pallet::<T>::fractionalize(
+		RawOrigin::Signed(owner.clone()).into(),
+		kitty_id,
+		100,
+		T::KittyStake::get(),
+	)
+	.expect("kitty fractionalized");
+	kitty_id
+}
+
+// 提交繁殖请求并把区块推进到可以揭示的高度，返回两个父母
+fn committed_breeding<T: Config>(
+	owner: &T::AccountId,
+	salt: T::Hash,
+) -> (T::KittyIndex, T::KittyIndex) {
+	let kitty_id_1 = create_kitty::<T>(owner);
+	let kitty_id_2 = create_kitty::<T>(owner);
+	Pallet::<T>::commit_breed(
+		RawOrigin::Signed(owner.clone()).into(),
+		kitty_id_1,
+		kitty_id_2,
+		T::Hashing::hash_of(&(owner, &salt)),
+	)
+	.expect("breeding committed");
+	let reveal_at = frame_system::Pallet::<T>::block_number() + T::RevealDelay::get();
+	frame_system::BlockHash::<T>::insert(reveal_at, T::Hashing::hash_of(&reveal_at));
+	frame_system::Pallet::<T>::set_block_number(reveal_at + 1u32.into());
+	(kitty_id_1, kitty_id_2)
+}
+
+// 为kitty设置最长的名字和元数据URI，由拥有者质押押金
+fn name_kitty<T: Config>(owner: &T::AccountId, kitty_id: T::KittyIndex) {
+	Pallet::<T>::set_name(
+		RawOrigin::Signed(owner.clone()).into(),
+		kitty_id,
+		vec![b'n'; T::MaxNameLength::get() as usize],
+	)
+	.expect("kitty named");
+	Pallet::<T>::set_metadata_uri(
+		RawOrigin::Signed(owner.clone()).into(),
+		kitty_id,
+		Some(vec![b'u'; T::MaxMetadataUriLength::get() as usize]),
+	)
+	.expect("metadata uri set");
+}
+
+// 把资产评分提交者集合填充到指定的数量
+fn fill_asset_authorities<T: Config>(len: u32) {
+	AssetAuthorities::<T>::mutate(|authorities| {
+		let mut index = 0;
+		while (authorities.len() as u32) < len {
+			authorities.try_push(account("authority", index, SEED)).expect("below the limit");
+			index += 1;
+		}
+	});
+}
+
+benchmarks! {
+	where_clause { where T::Assets: fungibles::Create<T::AccountId> }
+
+	create {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id = Pallet::<T>::next_kitty_id();
+	} : _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert_last_event::<T>(
+			Event::KittyCreatedAndBreed(caller, kitty_id).into()
+		);
+	}
+
+	breed {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id_1 = create_kitty::<T>(&caller);
+		let kitty_id_2 = create_kitty::<T>(&caller);
+		let child_id = Pallet::<T>::next_kitty_id();
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id_1, kitty_id_2)
+	verify {
+		assert_eq!(Pallet::<T>::kitty_owner(child_id), Some(caller));
+		assert_eq!(Pallet::<T>::kitty_parents(child_id), Some((kitty_id_1, kitty_id_2)));
+	}
+
+	commit_create {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let commitment = T::Hashing::hash_of(&(&caller, T::Hash::default()));
+		let reveal_at = frame_system::Pallet::<T>::block_number() + T::RevealDelay::get();
+	} : _(RawOrigin::Signed(caller.clone()), commitment)
+	verify {
+		assert_last_event::<T>(Event::DnaRequested(caller, reveal_at).into());
+	}
+
+	commit_breed {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id_1 = create_kitty::<T>(&caller);
+		let kitty_id_2 = create_kitty::<T>(&caller);
+		let commitment = T::Hashing::hash_of(&(&caller, T::Hash::default()));
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id_1, kitty_id_2, commitment)
+	verify {
+		assert_eq!(PendingBreedingParents::<T>::get(kitty_id_1), 1);
+		assert_eq!(PendingBreedingParents::<T>::get(kitty_id_2), 1);
+	}
+
+	// 最坏情况：揭示繁殖请求，需要解除父母的锁定并记录族谱
+	reveal {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let salt = T::Hash::default();
+		let (kitty_id_1, kitty_id_2) = committed_breeding::<T>(&caller, salt);
+		let child_id = Pallet::<T>::next_kitty_id();
+	} : _(RawOrigin::Signed(caller.clone()), salt)
+	verify {
+		assert_eq!(Pallet::<T>::kitty_owner(child_id), Some(caller));
+		assert_eq!(Pallet::<T>::kitty_parents(child_id), Some((kitty_id_1, kitty_id_2)));
+	}
+
+	// 最坏情况：放弃已经到期的繁殖请求，需要没收质押并解除父母的锁定
+	cancel_dna_request {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		committed_breeding::<T>(&caller, T::Hash::default());
+	} : _(RawOrigin::Signed(caller.clone()))
+	verify {
+		assert!(!DnaRequests::<T>::contains_key(&caller));
+	}
+
+	transfer {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let recipient = funded_account::<T>("recipient", 0);
+		let kitty_id = create_kitty::<T>(&caller);
+		// 最坏情况：kitty同时在出售、已授权并且有待领取的礼物，转移时都需要清除
+		KittiesShop::<T>::insert(kitty_id, Some(T::KittyStake::get()));
+		KittyApprovals::<T>::insert(kitty_id, recipient.clone());
+		PendingGifts::<T>::insert(kitty_id, recipient.clone());
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id, recipient.clone())
+	verify {
+		assert_last_event::<T>(
+			Event::KittyTransferred(caller, recipient, kitty_id).into()
+		);
+	}
+
+	// 最坏情况：由拥有者授权的操作员设置授权
+	approve {
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = create_kitty::<T>(&owner);
+		let caller: T::AccountId = whitelisted_caller();
+		Pallet::<T>::set_approval_for_all(
+			RawOrigin::Signed(owner.clone()).into(),
+			caller.clone(),
+			true,
+		).expect("operator approved");
+		let delegate: T::AccountId = account("delegate", 0, SEED);
+	} : _(RawOrigin::Signed(caller), kitty_id, Some(delegate.clone()))
+	verify {
+		assert_last_event::<T>(Event::KittyApproved(owner, Some(delegate), kitty_id).into());
+	}
+
+	set_approval_for_all {
+		let caller: T::AccountId = whitelisted_caller();
+		let operator: T::AccountId = account("operator", 0, SEED);
+	} : _(RawOrigin::Signed(caller.clone()), operator.clone(), true)
+	verify {
+		assert_last_event::<T>(Event::ApprovalForAll(caller, operator, true).into());
+	}
+
+	// 最坏情况：由操作员转移，kitty同时在出售、已授权并且有待领取的礼物
+	transfer_from {
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = create_kitty::<T>(&owner);
+		let caller: T::AccountId = whitelisted_caller();
+		Pallet::<T>::set_approval_for_all(
+			RawOrigin::Signed(owner.clone()).into(),
+			caller.clone(),
+			true,
+		).expect("operator approved");
+		let recipient = funded_account::<T>("recipient", 0);
+		KittiesShop::<T>::insert(kitty_id, Some(T::KittyStake::get()));
+		KittyApprovals::<T>::insert(kitty_id, recipient.clone());
+		PendingGifts::<T>::insert(kitty_id, recipient.clone());
+	} : _(RawOrigin::Signed(caller), kitty_id, recipient.clone())
+	verify {
+		assert_last_event::<T>(Event::KittyTransferred(owner, recipient, kitty_id).into());
+	}
+
+	gift {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller);
+		let recipient: T::AccountId = account("recipient", 0, SEED);
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id, recipient.clone())
+	verify {
+		assert_last_event::<T>(Event::KittyGiftOffered(caller, recipient, kitty_id).into());
+	}
+
+	cancel_gift {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller);
+		PendingGifts::<T>::insert(kitty_id, account::<T::AccountId>("recipient", 0, SEED));
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert_last_event::<T>(Event::KittyGiftCancelled(caller, kitty_id).into());
+	}
+
+	// 最坏情况：kitty同时在出售并且已授权，领取时都需要清除
+	claim_gift {
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = create_kitty::<T>(&owner);
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		Pallet::<T>::gift(RawOrigin::Signed(owner.clone()).into(), kitty_id, caller.clone())
+			.expect("kitty gifted");
+		KittiesShop::<T>::insert(kitty_id, Some(T::KittyStake::get()));
+		KittyApprovals::<T>::insert(kitty_id, owner.clone());
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert_last_event::<T>(Event::KittyTransferred(owner, caller, kitty_id).into());
+	}
+
+	sell {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller);
+		let price = Some(T::KittyStake::get());
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id, price)
+	verify {
+		assert_last_event::<T>(
+			Event::KittyInSell(caller, kitty_id, price).into()
+		);
+	}
+
+	sell_for_asset {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller);
+		// 上架时要求资产已存在，先创建一个资产
+		let asset_id = AssetIdOf::<T>::decode(&mut TrailingZeroInput::zeroes())
+			.expect("infinite input; qed");
+		<T::Assets as fungibles::Create<_>>::create(asset_id, caller.clone(), true, 1u32.into())?;
+		let price = T::KittyStake::get();
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id, asset_id, price)
+	verify {
+		assert_last_event::<T>(
+			Event::KittyInSellForAsset(caller, kitty_id, asset_id, price).into()
+		);
+	}
+
+	buy {
+		let seller = funded_account::<T>("seller", 0);
+		let kitty_id = create_kitty::<T>(&seller);
+		Pallet::<T>::sell(
+			RawOrigin::Signed(seller.clone()).into(),
+			kitty_id,
+			Some(T::KittyStake::get()),
+		).expect("kitty listed");
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		// 最坏情况：二次销售需要支付版税，购买时还需要清除授权和待领取的礼物
+		KittyCreators::<T>::insert(kitty_id, funded_account::<T>("creator", 0));
+		KittyApprovals::<T>::insert(kitty_id, caller.clone());
+		PendingGifts::<T>::insert(kitty_id, caller.clone());
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert_last_event::<T>(
+			Event::KittyTransferred(seller, caller, kitty_id).into()
+		);
+	}
+
+	make_offer {
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = create_kitty::<T>(&owner);
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let amount = T::KittyStake::get();
+		let expires_at = frame_system::Pallet::<T>::block_number() + 10u32.into();
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id, amount, expires_at)
+	verify {
+		assert_last_event::<T>(
+			Event::OfferMade(caller, kitty_id, amount, expires_at).into()
+		);
+	}
+
+	withdraw_offer {
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = create_kitty::<T>(&owner);
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let expires_at = frame_system::Pallet::<T>::block_number() + 10u32.into();
+		Pallet::<T>::make_offer(
+			RawOrigin::Signed(caller.clone()).into(),
+			kitty_id,
+			T::KittyStake::get(),
+			expires_at,
+		).expect("offer made");
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert_last_event::<T>(
+			Event::OfferWithdrawn(caller, kitty_id).into()
+		);
+	}
+
+	accept_offer {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller);
+		let bidder = funded_account::<T>("bidder", 0);
+		let amount = T::KittyStake::get();
+		let expires_at = frame_system::Pallet::<T>::block_number() + 10u32.into();
+		Pallet::<T>::make_offer(
+			RawOrigin::Signed(bidder.clone()).into(),
+			kitty_id,
+			amount,
+			expires_at,
+		).expect("offer made");
+		// 最坏情况：需要支付版税，kitty同时在出售、已授权并且有待领取的礼物
+		KittyCreators::<T>::insert(kitty_id, funded_account::<T>("creator", 0));
+		KittiesShop::<T>::insert(kitty_id, Some(amount));
+		KittyApprovals::<T>::insert(kitty_id, bidder.clone());
+		PendingGifts::<T>::insert(kitty_id, bidder.clone());
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id, bidder.clone())
+	verify {
+		assert_last_event::<T>(
+			Event::KittyTransferred(caller, bidder, kitty_id).into()
+		);
+	}
+
+	open_trade {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller);
+		let counterparty = funded_account::<T>("counterparty", 0);
+		let other_id = create_kitty::<T>(&counterparty);
+		let deadline = frame_system::Pallet::<T>::block_number() + 10u32.into();
+		let trade_id = Pallet::<T>::next_trade_id();
+		// 最坏情况：托管时需要撤下上架信息和待领取的礼物
+		KittiesShop::<T>::insert(kitty_id, Some(T::KittyStake::get()));
+		PendingGifts::<T>::insert(kitty_id, counterparty.clone());
+	} : _(
+		RawOrigin::Signed(caller.clone()),
+		kitty_id,
+		counterparty,
+		TradeConsideration::Kitty(other_id),
+		deadline
+	)
+	verify {
+		assert!(Trades::<T>::contains_key(trade_id));
+		assert_eq!(Pallet::<T>::escrowed_kitties(kitty_id), Some(trade_id));
+	}
+
+	lock_trade {
+		let maker = funded_account::<T>("maker", 0);
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let (trade_id, _, other_id) = open_kitty_swap::<T>(&maker, &caller);
+		// 最坏情况：托管时需要撤下上架信息和待领取的礼物
+		KittiesShop::<T>::insert(other_id, Some(T::KittyStake::get()));
+		PendingGifts::<T>::insert(other_id, maker);
+	} : _(RawOrigin::Signed(caller.clone()), trade_id)
+	verify {
+		assert_last_event::<T>(Event::TradeLocked(trade_id, caller).into());
+	}
+
+	confirm_trade {
+		let maker = funded_account::<T>("maker", 0);
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let (trade_id, kitty_id, other_id) = open_kitty_swap::<T>(&maker, &caller);
+		Pallet::<T>::lock_trade(RawOrigin::Signed(caller.clone()).into(), trade_id)
+			.expect("trade locked");
+		Pallet::<T>::confirm_trade(RawOrigin::Signed(maker.clone()).into(), trade_id)
+			.expect("maker confirmed");
+	} : _(RawOrigin::Signed(caller.clone()), trade_id)
+	verify {
+		assert_last_event::<T>(Event::TradeExecuted(trade_id).into());
+		assert_eq!(Pallet::<T>::kitty_owner(kitty_id), Some(caller));
+		assert_eq!(Pallet::<T>::kitty_owner(other_id), Some(maker));
+	}
+
+	cancel_trade {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller);
+		let taker = funded_account::<T>("taker", 0);
+		let deadline = frame_system::Pallet::<T>::block_number() + 10u32.into();
+		let trade_id = Pallet::<T>::next_trade_id();
+		// 乙方已质押资金，取消时需要退回
+		Pallet::<T>::open_trade(
+			RawOrigin::Signed(caller.clone()).into(),
+			kitty_id,
+			taker.clone(),
+			TradeConsideration::Funds(T::KittyStake::get()),
+			deadline,
+		).expect("trade opened");
+		Pallet::<T>::lock_trade(RawOrigin::Signed(taker).into(), trade_id)
+			.expect("trade locked");
+	} : _(RawOrigin::Signed(caller.clone()), trade_id)
+	verify {
+		assert_last_event::<T>(Event::TradeCancelled(trade_id, caller).into());
+	}
+
+	fractionalize {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller);
+		let reserve_price = T::KittyStake::get();
+		// 最坏情况：碎片化时需要撤下上架信息和待领取的礼物
+		KittiesShop::<T>::insert(kitty_id, Some(reserve_price));
+		PendingGifts::<T>::insert(kitty_id, funded_account::<T>("recipient", 0));
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id, 100, reserve_price)
+	verify {
+		assert_last_event::<T>(
+			Event::KittyFractionalized(caller, kitty_id, 100, reserve_price).into()
+		);
+	}
+
+	transfer_shares {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id = fractionalized_kitty::<T>(&caller);
+		let recipient: T::AccountId = account("recipient", 0, SEED);
+		// 最坏情况：接收人已持有份额
+		KittyShares::<T>::insert(kitty_id, &recipient, 1);
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id, recipient.clone(), 50)
+	verify {
+		assert_eq!(Pallet::<T>::kitty_shares(kitty_id, &recipient), 51);
+	}
+
+	redeem {
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = fractionalized_kitty::<T>(&owner);
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		// 最坏情况：赎回人不是原拥有者，需要转移kitty
+		Pallet::<T>::transfer_shares(
+			RawOrigin::Signed(owner).into(),
+			kitty_id,
+			caller.clone(),
+			100,
+		).expect("shares transferred");
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert_eq!(Pallet::<T>::kitty_owner(kitty_id), Some(caller));
+	}
+
+	buyout {
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = fractionalized_kitty::<T>(&owner);
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert_eq!(Pallet::<T>::kitty_owner(kitty_id), Some(caller));
+	}
+
+	claim_buyout_proceeds {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id = fractionalized_kitty::<T>(&caller);
+		let buyer = funded_account::<T>("buyer", 0);
+		// 最坏情况：不是最后一个领取的持有人，需要按比例计算
+		Pallet::<T>::transfer_shares(
+			RawOrigin::Signed(caller.clone()).into(),
+			kitty_id,
+			buyer.clone(),
+			1,
+		).expect("shares transferred");
+		Pallet::<T>::buyout(RawOrigin::Signed(buyer).into(), kitty_id).expect("bought out");
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert_eq!(Pallet::<T>::kitty_shares(kitty_id, &caller), 0);
+	}
+
+	// 最坏情况：kitty有名字、元数据URI、资产评分并且在出售，销毁时都需要清除并退还押金
+	burn {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller);
+		name_kitty::<T>(&caller, kitty_id);
+		KittyAssets::<T>::insert(kitty_id, 100);
+		KittiesShop::<T>::insert(kitty_id, Some(T::KittyStake::get()));
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id)
+	verify {
+		assert_last_event::<T>(Event::KittyBurned(caller, kitty_id).into());
+	}
+
+	// 最坏情况：名字和元数据URI都是最长的，需要退还之前的押金
+	set_name {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller);
+		name_kitty::<T>(&caller, kitty_id);
+		let name = vec![b'm'; T::MaxNameLength::get() as usize];
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id, name.clone())
+	verify {
+		assert_last_event::<T>(Event::KittyNamed(caller, kitty_id, name).into());
+	}
+
+	set_metadata_uri {
+		let caller: T::AccountId = whitelisted_caller();
+		fund_account::<T>(&caller);
+		let kitty_id = create_kitty::<T>(&caller);
+		name_kitty::<T>(&caller, kitty_id);
+		let uri = Some(vec![b'v'; T::MaxMetadataUriLength::get() as usize]);
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id, uri.clone())
+	verify {
+		assert_last_event::<T>(Event::KittyMetadataUriSet(caller, kitty_id, uri).into());
+	}
+
+	// 最坏情况：提交者在已满的集合末尾
+	update_kitty_asset {
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = create_kitty::<T>(&owner);
+		fill_asset_authorities::<T>(T::MaxAssetAuthorities::get() - 1);
+		let caller: T::AccountId = whitelisted_caller();
+		Pallet::<T>::add_asset_authority(
+			T::AssetAuthorityOrigin::successful_origin(),
+			caller.clone(),
+		)?;
+	} : _(RawOrigin::Signed(caller.clone()), kitty_id, 100)
+	verify {
+		assert_last_event::<T>(Event::KittyAssetUpdated(Some(caller), kitty_id, 100).into());
+	}
+
+	// 签名在 `validate_unsigned` 中验证，调用本身不验证签名
+	update_kitty_asset_unsigned_with_signed_payload {
+		let owner = funded_account::<T>("owner", 0);
+		let kitty_id = create_kitty::<T>(&owner);
+		fill_asset_authorities::<T>(T::MaxAssetAuthorities::get() - 1);
+		let public = T::Public::decode(&mut TrailingZeroInput::zeroes())
+			.expect("infinite input; qed");
+		Pallet::<T>::add_asset_authority(
+			T::AssetAuthorityOrigin::successful_origin(),
+			public.clone().into_account(),
+		)?;
+		let signature = T::Signature::decode(&mut TrailingZeroInput::zeroes())
+			.expect("infinite input; qed");
+		let payload = KittyAssetPayload { kitty_id, asset: 100, public };
+	} : _(RawOrigin::None, payload, signature)
+	verify {
+		assert_last_event::<T>(Event::KittyAssetUpdated(None, kitty_id, 100).into());
+	}
+
+	// 最坏情况：集合中只差一个就满，需要检查所有已有的提交者
+	add_asset_authority {
+		fill_asset_authorities::<T>(T::MaxAssetAuthorities::get() - 1);
+		let who: T::AccountId = account("new_authority", 0, SEED);
+		let origin = T::AssetAuthorityOrigin::successful_origin();
+	} : _<T::Origin>(origin, who.clone())
+	verify {
+		assert_last_event::<T>(Event::AssetAuthorityAdded(who).into());
+	}
+
+	// 最坏情况：移除已满集合中的最后一个
+	remove_asset_authority {
+		fill_asset_authorities::<T>(T::MaxAssetAuthorities::get());
+		let who = Pallet::<T>::asset_authorities().last().cloned().expect("authorities are full");
+		let origin = T::AssetAuthorityOrigin::successful_origin();
+	} : _<T::Origin>(origin, who.clone())
+	verify {
+		assert_last_event::<T>(Event::AssetAuthorityRemoved(who).into());
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,68 @@
+//! Kitty DNA 混合策略
+//!
+//! `breed` 通过 `Config::DnaMixer` 把父母的 DNA 与一段随机数混合成子 kitty 的 DNA，
+//! 测试链可以换用不同的策略来调整游戏平衡，而不需要修改 pallet。
+
+use frame_support::traits::Get;
+use sp_io::hashing::blake2_256;
+use sp_runtime::Permill;
+use sp_std::marker::PhantomData;
+
+/// Kitty DNA，一共 16 个基因，每个字节为一个基因
+pub type Dna = [u8; 16];
+
+/// DNA 混合策略
+pub trait DnaMixer {
+	/// 根据父母的 DNA 和一段由 `T::Randomness` 产生的随机数，生成子 kitty 的 DNA
+	fn mix(dna_1: &Dna, dna_2: &Dna, selector: &Dna) -> Dna;
+}
+
+/// 默认策略：按随机数的位从父母双方选择基因位
+/// `(dna_1 & selector) | (dna_2 & !selector)`
+impl DnaMixer for () {
+	fn mix(dna_1: &Dna, dna_2: &Dna, selector: &Dna) -> Dna {
+		let mut new_dna = [0u8; 16];
+		for i in 0..dna_1.len() {
+			new_dna[i] = (dna_1[i] & selector[i]) | (dna_2[i] & !selector[i]);
+		}
+		new_dna
+	}
+}
+
+/// 显性基因策略
+///
+/// 每个基因整体继承自父母中的一方：高 4 位代表显性强度，强度高的一方胜出，
+/// 强度相同时由随机数决定。之后每个基因都以 `MutationChance` 的概率发生突变，
+/// 随机翻转其中一位：每个基因取一个 `u16` 随机数，小于 `MutationChance * 65536`
+/// 时突变，因此 `Permill::zero()` 从不突变，`Permill::one()` 时每个基因都会突变。
+pub struct DominantGeneMixer<MutationChance>(PhantomData<MutationChance>);
+
+impl<MutationChance: Get<Permill>> DnaMixer for DominantGeneMixer<MutationChance> {
+	fn mix(dna_1: &Dna, dna_2: &Dna, selector: &Dna) -> Dna {
+		// 从选择器再派生随机数，避免突变与基因选择使用同一批随机位：
+		// `mutation_seed` 每 2 字节决定一个基因是否突变，`flip_seed` 决定翻转哪一位
+		let mutation_seed = blake2_256(selector);
+		let flip_seed = blake2_256(&mutation_seed);
+		let threshold = MutationChance::get().mul_floor(u16::MAX as u32 + 1);
+
+		let mut new_dna = [0u8; 16];
+		for i in 0..dna_1.len() {
+			let (dominance_1, dominance_2) = (dna_1[i] >> 4, dna_2[i] >> 4);
+			new_dna[i] = if dominance_1 > dominance_2 {
+				dna_1[i]
+			} else if dominance_2 > dominance_1 {
+				dna_2[i]
+			} else if selector[i] & 1 == 1 {
+				dna_1[i]
+			} else {
+				dna_2[i]
+			};
+
+			let draw = u16::from_le_bytes([mutation_seed[2 * i], mutation_seed[2 * i + 1]]);
+			if (draw as u32) < threshold {
+				new_dna[i] ^= 1 << (flip_seed[i] & 0b111);
+			}
+		}
+		new_dna
+	}
+}
@@ -2,30 +2,89 @@
 
 pub use pallet::*;
 
+pub mod dna;
+pub mod weights;
+
+pub use dna::{DnaMixer, DominantGeneMixer};
+pub use weights::WeightInfo;
+
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
 mod tests;
 
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
 #[frame_support::pallet]
 pub mod pallet {
+	use crate::dna::DnaMixer;
+	use crate::weights::WeightInfo;
 	use frame_support::pallet_prelude::*;
-	use frame_support::traits::{Currency, ExistenceRequirement, Randomness, ReservableCurrency};
+	use frame_support::traits::{
+		fungibles, BalanceStatus, Currency, ExistenceRequirement, Randomness, ReservableCurrency,
+	};
 
 	use frame_support::inherent::Vec;
-	use frame_system::offchain::SendSignedTransaction;
-	use frame_system::offchain::{AppCrypto, CreateSignedTransaction, Signer};
+	use frame_system::offchain::{
+		AppCrypto, CreateSignedTransaction, SendUnsignedTransaction, SignedPayload, Signer,
+	};
 	use frame_system::pallet_prelude::*;
-	use sp_core::blake2_128;
+	#[cfg(feature = "std")]
+	use serde::{Deserialize, Serialize};
+	use sp_core::crypto::KeyTypeId;
 	use sp_io::hashing::blake2_128;
 	use sp_io::offchain_index;
 	use sp_runtime::offchain::storage::StorageValueRef;
-	use sp_runtime::traits::{AtLeast32BitUnsigned, Bounded, CheckedAdd, Zero};
-	use sp_runtime::transaction_validity::InvalidTransaction::Call;
+	use sp_runtime::offchain::storage_lock::{BlockAndTime, StorageLock};
+	use sp_runtime::offchain::Duration;
+	use sp_runtime::traits::{
+		AtLeast32BitUnsigned, Bounded, CheckedAdd, Hash, IdentifyAccount, One, Zero,
+	};
+	use sp_runtime::transaction_validity::{
+		InvalidTransaction, TransactionSource, TransactionValidity, ValidTransaction,
+	};
+	use sp_runtime::{Perbill, Permill};
+
+	/// 链下工作机签名交易使用的密钥类型，可以通过 `author_insertKey` RPC 插入密钥
+	pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"kitt");
+
+	/// 基于 `KEY_TYPE` 的 sr25519 签名类型
+	pub mod crypto {
+		use super::KEY_TYPE;
+		use sp_core::sr25519::Signature as Sr25519Signature;
+		use sp_runtime::{
+			app_crypto::{app_crypto, sr25519},
+			traits::Verify,
+			MultiSignature, MultiSigner,
+		};
+
+		app_crypto!(sr25519, KEY_TYPE);
+
+		pub struct KittiesAuthId;
+
+		// runtime 使用
+		impl frame_system::offchain::AppCrypto<MultiSigner, MultiSignature> for KittiesAuthId {
+			type RuntimeAppPublic = Public;
+			type GenericSignature = sp_core::sr25519::Signature;
+			type GenericPublic = sp_core::sr25519::Public;
+		}
+
+		impl
+			frame_system::offchain::AppCrypto<
+				<Sr25519Signature as Verify>::Signer,
+				Sr25519Signature,
+			> for KittiesAuthId
+		{
+			type RuntimeAppPublic = Public;
+			type GenericSignature = sp_core::sr25519::Signature;
+			type GenericPublic = sp_core::sr25519::Public;
+		}
+	}
 
 	///接口配置
 	#[pallet::config]
-	pub trait Config: frame_system::Config {
+	pub trait Config: frame_system::Config + CreateSignedTransaction<Call<Self>> {
 		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 		type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
 		//质押资产类型
@@ -44,7 +103,52 @@ pub mod pallet {
 		#[pallet::constant]
 		type MaxKittyIndex: Get<u32>;
 
+		// 链下工作机提交kitty资产评分时使用的签名类型
 		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+
+		// 管理资产评分提交者集合的权限，一般为 root
+		type AssetAuthorityOrigin: EnsureOrigin<Self::Origin>;
+
+		// 资产评分提交者数量上限
+		#[pallet::constant]
+		type MaxAssetAuthorities: Get<u32>;
+
+		// 繁殖时混合父母DNA的策略，`()` 为默认的按位选择策略
+		type DnaMixer: DnaMixer;
+
+		// 提交-揭示模式下，DNA请求提交后要经过多少个区块才能揭示
+		#[pallet::constant]
+		type RevealDelay: Get<Self::BlockNumber>;
+
+		// 繁殖后父母kitty的锁定期，锁定期内不能销毁
+		#[pallet::constant]
+		type BreedingLockPeriod: Get<Self::BlockNumber>;
+
+		// kitty名字的最大长度
+		#[pallet::constant]
+		type MaxNameLength: Get<u32>;
+
+		// kitty元数据URI的最大长度
+		#[pallet::constant]
+		type MaxMetadataUriLength: Get<u32>;
+
+		// 设置名字和元数据URI时每字节需要质押的押金
+		#[pallet::constant]
+		type MetadataDepositPerByte: Get<BalanceOf<Self>>;
+
+		// 二次销售时支付给kitty创作者（创建者或繁殖者）的版税比例
+		#[pallet::constant]
+		type RoyaltyRate: Get<Permill>;
+
+		// 除原生代币外，kitty还可以用这里的资产（如游戏内代币）定价出售
+		type Assets: fungibles::Transfer<Self::AccountId, Balance = BalanceOf<Self>>;
+
+		// 同一个区块到期的托管交易数量上限
+		#[pallet::constant]
+		type MaxTradesPerDeadline: Get<u32>;
+
+		// 交易权重
+		type WeightInfo: WeightInfo;
 	}
 
 	#[pallet::pallet]
@@ -56,6 +160,100 @@ pub mod pallet {
 		pub dna: [u8; 16],
 	}
 
+	/// 对外查询用的kitty完整信息
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct KittyInfo<AccountId, Balance, AssetId> {
+		pub dna: [u8; 16],
+		pub owner: AccountId,
+		pub creator: Option<AccountId>,
+		pub name: Vec<u8>,
+		pub metadata_uri: Option<Vec<u8>>,
+		pub price: Option<Balance>,
+		// 售价使用的资产，None 为原生代币
+		pub price_asset: Option<AssetId>,
+		pub asset: Option<u32>,
+	}
+
+	/// 对kitty的报价，报价金额在报价期间一直质押
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub struct KittyOffer<Balance, BlockNumber> {
+		pub amount: Balance,
+		// 到达该区块后报价失效
+		pub expires_at: BlockNumber,
+	}
+
+	/// 托管交易的编号
+	pub type TradeId = u32;
+
+	/// 托管交易中乙方拿出的对价：一笔资金或另一只kitty
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub enum TradeConsideration<Balance, KittyIndex> {
+		Funds(Balance),
+		Kitty(KittyIndex),
+	}
+
+	/// 双方托管的kitty交易，甲方托管kitty，乙方托管对价，双方确认后原子交换
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub struct KittyTrade<AccountId, Balance, KittyIndex, BlockNumber> {
+		pub maker: AccountId,
+		pub kitty_id: KittyIndex,
+		pub taker: AccountId,
+		pub consideration: TradeConsideration<Balance, KittyIndex>,
+		// 乙方是否已托管对价
+		pub taker_locked: bool,
+		pub maker_confirmed: bool,
+		pub taker_confirmed: bool,
+		// 到达该区块时交易仍未完成则自动解除
+		pub deadline: BlockNumber,
+	}
+
+	/// 碎片化的kitty：份额由pallet记账，持有全部份额或支付保留价即可赎回kitty
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub struct KittyFraction<Balance> {
+		pub total_shares: u32,
+		pub reserve_price: Balance,
+	}
+
+	/// 按保留价买断后，份额持有人按份额领取买断款，款项在领取前一直由买家质押
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub struct KittyBuyout<AccountId, Balance> {
+		pub buyer: AccountId,
+		pub remaining_proceeds: Balance,
+		pub remaining_shares: u32,
+	}
+
+	/// 链下工作机提交kitty资产评分时签名的数据
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo)]
+	pub struct KittyAssetPayload<Public, KittyIndex> {
+		pub kitty_id: KittyIndex,
+		pub asset: u32,
+		pub public: Public,
+	}
+
+	impl<T: Config> SignedPayload<T> for KittyAssetPayload<T::Public, T::KittyIndex> {
+		fn public(&self) -> T::Public {
+			self.public.clone()
+		}
+	}
+
+	/// 提交-揭示模式下待揭示的DNA请求
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, Debug, TypeInfo, MaxEncodedLen)]
+	pub struct DnaRequest<Hash, BlockNumber, KittyIndex> {
+		// 承诺值 hash(账户, salt)
+		pub commitment: Hash,
+		// 用这个区块的哈希作为随机源，揭示必须在它之后的区块进行，
+		// 区块哈希超出 `BlockHashCount` 被清理后请求过期，只能放弃
+		pub reveal_at: BlockNumber,
+		// 繁殖请求的父母，创建请求为 None
+		pub parents: Option<(KittyIndex, KittyIndex)>,
+	}
+
+	/// 查询接口每页返回的最大条数
+	pub const QUERY_PAGE_SIZE: u32 = 20;
+	/// 查询祖先时的最大代数
+	pub const MAX_ANCESTOR_DEPTH: u32 = 5;
+
 	#[pallet::type_value]
 	pub fn GetDefaultValue<T: Config>() -> T::KittyIndex {
 		0_u8.into()
@@ -63,6 +261,10 @@ pub mod pallet {
 	//账户余额
 	type BalanceOf<T> =
 		<<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+	//定价资产id
+	pub type AssetIdOf<T> = <<T as Config>::Assets as fungibles::Inspect<
+		<T as frame_system::Config>::AccountId,
+	>>::AssetId;
 
 	//最新kitty id
 	#[pallet::storage]
@@ -80,19 +282,267 @@ pub mod pallet {
 	#[pallet::getter(fn kitty_owner)]
 	pub type KittyOwner<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
 
+	// 存储繁殖出的kitty的父母
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_parents)]
+	pub type KittyParents<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, (T::KittyIndex, T::KittyIndex)>;
+
+	// 存储kitty的子kitty索引
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_children)]
+	pub type KittyChildren<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::KittyIndex,
+		Blake2_128Concat,
+		T::KittyIndex,
+		(),
+		OptionQuery,
+	>;
+
+	// 存储kitty的创作者，即创建或繁殖出这只kitty的账户
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_creators)]
+	pub type KittyCreators<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
+
+	// 存储账户拥有的kitty索引
+	#[pallet::storage]
+	#[pallet::getter(fn owned_kitties)]
+	pub type OwnedKitties<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::KittyIndex,
+		(),
+		OptionQuery,
+	>;
+
+	// 存储单个kitty被授权转移的账户
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_approvals)]
+	pub type KittyApprovals<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
+
+	// 存储拥有者授权的操作员，操作员可以转移拥有者的所有kitty
+	#[pallet::storage]
+	#[pallet::getter(fn operator_approvals)]
+	pub type OperatorApprovals<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		Blake2_128Concat,
+		T::AccountId,
+		(),
+		OptionQuery,
+	>;
+
+	// 存储待领取的赠送kitty及接收人
+	#[pallet::storage]
+	#[pallet::getter(fn pending_gifts)]
+	pub type PendingGifts<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, T::AccountId>;
+
+	// 存储对kitty的报价，每个账户对同一只kitty只能有一个报价
+	#[pallet::storage]
+	#[pallet::getter(fn offers)]
+	pub type Offers<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::KittyIndex,
+		Blake2_128Concat,
+		T::AccountId,
+		KittyOffer<BalanceOf<T>, T::BlockNumber>,
+		OptionQuery,
+	>;
+
+	// 按失效区块索引报价，供 `on_idle` 清理过期报价
+	#[pallet::storage]
+	pub type OfferExpiries<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::BlockNumber,
+		Blake2_128Concat,
+		(T::KittyIndex, T::AccountId),
+		(),
+		OptionQuery,
+	>;
+
+	// 下一个需要检查过期报价的区块，从未有过报价时为 None
+	#[pallet::storage]
+	pub type NextOfferExpiryCheck<T: Config> = StorageValue<_, T::BlockNumber>;
+
+	// 下一个托管交易的编号
+	#[pallet::storage]
+	#[pallet::getter(fn next_trade_id)]
+	pub type NextTradeId<T: Config> = StorageValue<_, TradeId, ValueQuery>;
+
+	// 存储进行中的托管交易
+	#[pallet::storage]
+	#[pallet::getter(fn trades)]
+	pub type Trades<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		TradeId,
+		KittyTrade<T::AccountId, BalanceOf<T>, T::KittyIndex, T::BlockNumber>,
+	>;
+
+	// 存储托管中的kitty及其所在的交易，托管期间不能转移、出售、赠送或销毁
+	#[pallet::storage]
+	#[pallet::getter(fn escrowed_kitties)]
+	pub type EscrowedKitties<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, TradeId>;
+
+	// 按截止区块索引托管交易，到期时自动解除
+	#[pallet::storage]
+	pub type TradeDeadlines<T: Config> = StorageMap<
+		_,
+		Twox64Concat,
+		T::BlockNumber,
+		BoundedVec<TradeId, T::MaxTradesPerDeadline>,
+		ValueQuery,
+	>;
+
+	// 存储碎片化的kitty，存在份额期间kitty不能转移、出售或繁殖
+	#[pallet::storage]
+	#[pallet::getter(fn fractions)]
+	pub type Fractions<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, KittyFraction<BalanceOf<T>>>;
+
+	// 份额账本：kitty id, 持有人 => 份额数量
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_shares)]
+	pub type KittyShares<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		T::KittyIndex,
+		Blake2_128Concat,
+		T::AccountId,
+		u32,
+		ValueQuery,
+	>;
+
+	// 存储被买断、份额持有人还未领取完买断款的kitty
+	#[pallet::storage]
+	#[pallet::getter(fn buyouts)]
+	pub type Buyouts<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, KittyBuyout<T::AccountId, BalanceOf<T>>>;
+
+	// 存储繁殖过的kitty的锁定截止区块
+	#[pallet::storage]
+	#[pallet::getter(fn breeding_locks)]
+	pub type BreedingLocks<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, T::BlockNumber>;
+
+	// 存储提交-揭示繁殖中作为父母、等待揭示的kitty及引用它的请求数，揭示或放弃前不能转移、
+	// 出售、碎片化或销毁，避免提交者看到结果后改变父母来重新随机
+	#[pallet::storage]
+	#[pallet::getter(fn pending_breeding_parents)]
+	pub type PendingBreedingParents<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, u32, ValueQuery>;
+
+	// 存储kitty的名字
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_names)]
+	pub type KittyNames<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, BoundedVec<u8, T::MaxNameLength>>;
+
+	// 存储kitty的元数据URI
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_metadata_uris)]
+	pub type KittyMetadataUris<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, BoundedVec<u8, T::MaxMetadataUriLength>>;
+
+	// 存储链下工作机计算出的kitty资产评分
+	#[pallet::storage]
+	#[pallet::getter(fn kitty_assets)]
+	pub type KittyAssets<T: Config> = StorageMap<_, Blake2_128Concat, T::KittyIndex, u32>;
+
+	// 允许提交kitty资产评分的账户，链下工作机的签名密钥对应的账户需要在其中
+	#[pallet::storage]
+	#[pallet::getter(fn asset_authorities)]
+	pub type AssetAuthorities<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxAssetAuthorities>, ValueQuery>;
+
+	// 存储名字和元数据URI的押金及押金的质押人
+	#[pallet::storage]
+	#[pallet::getter(fn metadata_deposits)]
+	pub type MetadataDeposits<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, (T::AccountId, BalanceOf<T>)>;
+
 	// 存储正在销售的kittyid 及价格
 	#[pallet::storage]
 	#[pallet::getter(fn kitties_list_for_sales)]
 	pub type KittiesShop<T: Config> =
 		StorageMap<_, Blake2_128Concat, T::KittyIndex, Option<BalanceOf<T>>, ValueQuery>;
 
+	// 存储以资产定价出售的kitty使用的资产，不存在时以原生代币定价
+	#[pallet::storage]
+	#[pallet::getter(fn listing_assets)]
+	pub type ListingAssets<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::KittyIndex, AssetIdOf<T>>;
+
+	// 存储每个账户待揭示的DNA请求，每个账户同时只能有一个
+	#[pallet::storage]
+	#[pallet::getter(fn dna_requests)]
+	pub type DnaRequests<T: Config> = StorageMap<
+		_,
+		Blake2_128Concat,
+		T::AccountId,
+		DnaRequest<T::Hash, T::BlockNumber, T::KittyIndex>,
+	>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		KittyCreatedAndBreed(T::AccountId, T::KittyIndex),
+		DnaRequested(T::AccountId, T::BlockNumber),
 		KittyTransferred(T::AccountId, T::AccountId, T::KittyIndex),
 		KittyInSell(T::AccountId, T::KittyIndex, Option<BalanceOf<T>>),
-		KittySold(T::AccountId, T::AccountId, T::KittyIndex),
+		KittyInSellForAsset(T::AccountId, T::KittyIndex, AssetIdOf<T>, BalanceOf<T>),
+		// 卖家, 买家, kitty id, 卖家所得, 版税，金额以上架时的定价资产计
+		KittySold(T::AccountId, T::AccountId, T::KittyIndex, BalanceOf<T>, BalanceOf<T>),
+		RoyaltyPaid(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		KittyBurned(T::AccountId, T::KittyIndex),
+		KittyNamed(T::AccountId, T::KittyIndex, Vec<u8>),
+		KittyMetadataUriSet(T::AccountId, T::KittyIndex, Option<Vec<u8>>),
+		KittyApproved(T::AccountId, Option<T::AccountId>, T::KittyIndex),
+		ApprovalForAll(T::AccountId, T::AccountId, bool),
+		KittyGiftOffered(T::AccountId, T::AccountId, T::KittyIndex),
+		KittyGiftCancelled(T::AccountId, T::KittyIndex),
+		KittyGiftClaimed(T::AccountId, T::AccountId, T::KittyIndex),
+		KittyAssetUpdated(Option<T::AccountId>, T::KittyIndex, u32),
+		OfferMade(T::AccountId, T::KittyIndex, BalanceOf<T>, T::BlockNumber),
+		OfferWithdrawn(T::AccountId, T::KittyIndex),
+		OfferAccepted(T::AccountId, T::AccountId, T::KittyIndex, BalanceOf<T>),
+		OfferExpired(T::AccountId, T::KittyIndex),
+		// 交易编号, 甲方, 乙方, 甲方的kitty, 乙方的对价, 截止区块
+		TradeOpened(
+			TradeId,
+			T::AccountId,
+			T::AccountId,
+			T::KittyIndex,
+			TradeConsideration<BalanceOf<T>, T::KittyIndex>,
+			T::BlockNumber,
+		),
+		TradeLocked(TradeId, T::AccountId),
+		TradeConfirmed(TradeId, T::AccountId),
+		TradeExecuted(TradeId),
+		TradeCancelled(TradeId, T::AccountId),
+		TradeExpired(TradeId),
+		// 拥有者, kitty id, 份额总数, 保留价
+		KittyFractionalized(T::AccountId, T::KittyIndex, u32, BalanceOf<T>),
+		SharesTransferred(T::AccountId, T::AccountId, T::KittyIndex, u32),
+		KittyRedeemed(T::AccountId, T::KittyIndex),
+		KittyBoughtOut(T::AccountId, T::KittyIndex, BalanceOf<T>),
+		// 持有人, kitty id, 份额, 领取的买断款
+		BuyoutProceedsClaimed(T::AccountId, T::KittyIndex, u32, BalanceOf<T>),
+		// 目标区块之前放弃DNA请求，质押已退还
+		DnaRequestCancelled(T::AccountId),
+		// 目标区块之后放弃DNA请求，没收的质押
+		DnaRequestForfeited(T::AccountId, BalanceOf<T>),
+		AssetAuthorityAdded(T::AccountId),
+		AssetAuthorityRemoved(T::AccountId),
 	}
 
 	#[pallet::error]
@@ -104,15 +554,95 @@ pub mod pallet {
 		NoBuySelf,
 		NotForSale,
 		NotEnoughBalance,
+		RequestAlreadyPending,
+		NoPendingRequest,
+		RevealTooEarly,
+		InvalidReveal,
+		KittyBreedingLocked,
+		NameTooLong,
+		MetadataUriTooLong,
+		NotApproved,
+		RecipientNotExist,
+		NoPendingGift,
+		NotGiftRecipient,
+		OfferAlreadyExists,
+		NoOffer,
+		OfferExpired,
+		InvalidOfferExpiry,
+		KittyInEscrow,
+		TradeNotFound,
+		NotTradeParty,
+		TradeAlreadyLocked,
+		TradeNotLocked,
+		TradeAlreadyConfirmed,
+		InvalidTradeDeadline,
+		TooManyTrades,
+		TradeIdOverflow,
+		KittyFractionalized,
+		NotFractionalized,
+		InvalidShareCount,
+		NotEnoughShares,
+		NoBuyout,
+		BuyoutPending,
+		RequestExpired,
+		KittyBreedingPending,
+		NotAssetAuthority,
+		AlreadyAssetAuthority,
+		TooManyAssetAuthorities,
+		UnknownAsset,
+		InvalidReservePrice,
+	}
+
+	/// 创世配置：预先铸造的kitty列表 (所有者, DNA)，以及资产评分提交者
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub kitties: Vec<(T::AccountId, [u8; 16])>,
+		pub asset_authorities: Vec<T::AccountId>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self { kitties: Vec::new(), asset_authorities: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			let authorities: BoundedVec<_, _> = self
+				.asset_authorities
+				.clone()
+				.try_into()
+				.expect("too many genesis asset authorities");
+			AssetAuthorities::<T>::put(authorities);
+			for (owner, dna) in &self.kitties {
+				// 与 create 一样为每只kitty质押，创世账户必须有足够的余额
+				T::Currency::reserve(owner, T::KittyStake::get())
+					.expect("genesis kitty owner must have enough balance for the stake");
+				Pallet::<T>::mint_kitty(owner, *dna).expect("genesis kitty id must not overflow");
+			}
+		}
 	}
 
 	const ONCHAIN_INDEX_KEY: &[u8] = b"kitty_pallet::index01";
-	#[derive(Debug, Encode, Decode, Default)]
-	struct IndexData<T: Config>(T::KittyIndex);
+	// 之前的kitty评分都已上链或已销毁，链下工作机从这里开始检查
+	const ASSET_CURSOR_KEY: &[u8] = b"kitty_pallet::asset_cursor";
+	const ASSET_LOCK_KEY: &[u8] = b"kitty_pallet::asset_lock";
+	const LOCK_BLOCK_EXPIRATION: u32 = 3; // in block number
+	const LOCK_TIMEOUT_EXPIRATION: u64 = 6000; // in milli-seconds
+
+	// 每次链下工作机最多为多少只kitty提交资产评分
+	const MAX_ASSETS_PER_RUN: u32 = 5;
+	const UNSIGNED_TXS_PRIORITY: u64 = 100;
+
+	// 创建kitty时写入链下索引的数据
+	#[derive(Debug, Encode, Decode)]
+	struct IndexingData<KittyIndex>(KittyIndex, [u8; 16]);
 
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::create())]
 		pub fn create(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
 			let dna = Self::random_value(&sender);
@@ -120,36 +650,110 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
+		#[pallet::weight(T::WeightInfo::breed())]
 		pub fn breed(
 			origin: OriginFor<T>,
 			kitty_id_1: T::KittyIndex,
 			kitty_id_2: T::KittyIndex,
 		) -> DispatchResultWithPostInfo {
 			let sender = ensure_signed(origin)?;
-			// 验证父母是不是同一个kitty
-			ensure!(kitty_id_1 != kitty_id_2, Error::<T>::SameParentId);
-
-			// 检查kitty_id是否存在且有效
-			let kitty_1 = Self::kitty_of_id(kitty_id_1).map_err(|_| Error::<T>::InvalidKittyId)?;
-			let kitty_2 = Self::kitty_of_id(kitty_id_2).map_err(|_| Error::<T>::InvalidKittyId)?;
-			let dna_1 = kitty_1.dna;
-			let dna_2 = kitty_2.dna;
-
 			// 生成一个随机数，混淆父母的dna,作为子kitty的独有基因
 			let selector = Self::random_value(&sender);
-
-			// 通过把父母的基因与子kitty的独有基因进行位与、位或，得到子Kitty的完整基因
-			let mut new_dna = [0u8; 16];
-			for i in 0..dna_1.len() {
-				new_dna[i] = (dna_1[i] & selector[i]) | (dna_2[i] & !selector[i]);
-			}
+			let new_dna = Self::breed_dna(kitty_id_1, kitty_id_2, &selector)?;
+			// 只能用自己拥有或被授权的kitty繁殖，繁殖会锁定父母
+			Self::ensure_can_breed(&sender, kitty_id_1)?;
+			Self::ensure_can_breed(&sender, kitty_id_2)?;
+			let child_id = Self::next_kitty_id();
 			// 质押并创建一个新kitty
 			Self::new_kitty_with_stake(&sender, new_dna)?;
+			Self::record_breeding(child_id, kitty_id_1, kitty_id_2);
 			Ok(().into())
 		}
 
-		#[pallet::weight(10_000)]
+		/// 提交-揭示模式创建kitty：先提交 hash(账户, salt)，`RevealDelay` 个区块后再调用 `reveal`
+		#[pallet::weight(T::WeightInfo::commit_create())]
+		pub fn commit_create(origin: OriginFor<T>, commitment: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			Self::commit_dna_request(&sender, commitment, None)
+		}
+
+		/// 提交-揭示模式繁殖kitty
+		#[pallet::weight(T::WeightInfo::commit_breed())]
+		pub fn commit_breed(
+			origin: OriginFor<T>,
+			kitty_id_1: T::KittyIndex,
+			kitty_id_2: T::KittyIndex,
+			commitment: T::Hash,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(kitty_id_1 != kitty_id_2, Error::<T>::SameParentId);
+			ensure!(Kitties::<T>::contains_key(kitty_id_1), Error::<T>::InvalidKittyId);
+			ensure!(Kitties::<T>::contains_key(kitty_id_2), Error::<T>::InvalidKittyId);
+			Self::ensure_can_breed(&sender, kitty_id_1)?;
+			Self::ensure_can_breed(&sender, kitty_id_2)?;
+			Self::ensure_tradable(kitty_id_1)?;
+			Self::ensure_tradable(kitty_id_2)?;
+			Self::commit_dna_request(&sender, commitment, Some((kitty_id_1, kitty_id_2)))?;
+			// 揭示前锁定父母
+			PendingBreedingParents::<T>::mutate(kitty_id_1, |count| *count += 1);
+			PendingBreedingParents::<T>::mutate(kitty_id_2, |count| *count += 1);
+			Ok(())
+		}
+
+		/// 揭示之前提交的 salt，用提交时还未产生的区块哈希生成DNA并创建kitty
+		#[pallet::weight(T::WeightInfo::reveal())]
+		pub fn reveal(origin: OriginFor<T>, salt: T::Hash) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let request = Self::dna_requests(&sender).ok_or(Error::<T>::NoPendingRequest)?;
+			// 验证 salt 与承诺值一致
+			ensure!(
+				T::Hashing::hash_of(&(&sender, &salt)) == request.commitment,
+				Error::<T>::InvalidReveal
+			);
+			// 目标区块的哈希要在下一个区块才能取到
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now > request.reveal_at, Error::<T>::RevealTooEarly);
+
+			let seed = Self::committed_random_value(&sender, &salt, request.reveal_at)
+				.ok_or(Error::<T>::RequestExpired)?;
+
+			DnaRequests::<T>::remove(&sender);
+			Self::release_breeding_parents(request.parents);
+			// 质押已在提交时完成，这里只创建kitty。父母在提交后一直锁定，
+			// 繁殖失败时直接返回错误，不能退回到随机DNA
+			match request.parents {
+				Some((kitty_id_1, kitty_id_2)) => {
+					let dna = Self::breed_dna(kitty_id_1, kitty_id_2, &seed)?;
+					let child_id = Self::next_kitty_id();
+					Self::mint_kitty(&sender, dna)?;
+					Self::record_breeding(child_id, kitty_id_1, kitty_id_2);
+				},
+				None => Self::mint_kitty(&sender, seed)?,
+			}
+			Ok(())
+		}
+
+		/// 放弃待揭示的DNA请求：目标区块之前放弃会退还质押；之后结果已经确定，
+		/// 放弃会没收质押，避免看到结果后放弃重来。过期的请求也通过这里清除
+		#[pallet::weight(T::WeightInfo::cancel_dna_request())]
+		pub fn cancel_dna_request(origin: OriginFor<T>) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let request = DnaRequests::<T>::take(&sender).ok_or(Error::<T>::NoPendingRequest)?;
+			Self::release_breeding_parents(request.parents);
+
+			let stake_amount = T::KittyStake::get();
+			let now = <frame_system::Pallet<T>>::block_number();
+			if now <= request.reveal_at {
+				T::Currency::unreserve(&sender, stake_amount);
+				Self::deposit_event(Event::DnaRequestCancelled(sender));
+			} else {
+				let (_, remaining) = T::Currency::slash_reserved(&sender, stake_amount);
+				Self::deposit_event(Event::DnaRequestForfeited(sender, stake_amount - remaining));
+			}
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::transfer())]
 		pub fn transfer(
 			origin: OriginFor<T>,
 			kitty_id: T::KittyIndex,
@@ -161,183 +765,1425 @@ pub mod pallet {
 			// 验证拥有者是否为当前操作者
 			ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), Error::<T>::NotOwner);
 
-			// 获取操作要质押的数量，并开始质押
-			let stake_amount = T::KittyStake::get();
-			T::Currency::reserve(&new_owner, stake_amount)
-				.map_err(|_| Error::<T>::NotEnoughBalance)?;
-			// 原有拥有者解除质押
-			T::Currency::unreserve(&sender, stake_amount);
-			// 保存新的拥有者关系
-			<KittyOwner<T>>::insert(&kitty_id, &new_owner);
-
-			Self::deposit_event(Event::KittyTransferred(sender, new_owner, kitty_id));
+			Self::do_transfer(kitty_id, &sender, &new_owner)?;
 			Ok(().into())
 		}
 
-		#[pallet::weight(1_000)]
-		pub fn sell(
+		/// 授权某个账户转移指定的kitty，传入 None 时取消授权
+		#[pallet::weight(T::WeightInfo::approve())]
+		pub fn approve(
 			origin: OriginFor<T>,
 			kitty_id: T::KittyIndex,
-			price: Option<BalanceOf<T>>,
-		) -> DispatchResultWithPostInfo {
-			let seller = ensure_signed(origin)?;
-			// 验证操作者是否为拥有者
-			ensure!(Self::kitty_owner(kitty_id) == Some(seller.clone()), Error::<T>::NotOwner);
-			// 给kitty设定价格，并保存关联有关系
-			KittiesShop::<T>::mutate_exists(kitty_id, |p| *p = Some(price));
+			delegate: Option<T::AccountId>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			// 拥有者和拥有者授权的操作员都可以设置授权
+			ensure!(
+				sender == owner || OperatorApprovals::<T>::contains_key(&owner, &sender),
+				Error::<T>::NotOwner
+			);
 
-			Self::deposit_event(Event::KittyInSell(seller, kitty_id, price));
-			Ok(().into())
-		}
+			match &delegate {
+				Some(delegate) => KittyApprovals::<T>::insert(kitty_id, delegate),
+				None => KittyApprovals::<T>::remove(kitty_id),
+			}
 
-		#[pallet::weight(1_000)]
-		pub fn buy(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResultWithPostInfo {
-			let buyer = ensure_signed(origin)?;
-			// 根据ID获取kitty所有者
-			let seller = KittyOwner::<T>::get(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
-			// 验证购买者是否为拥有者
-			ensure!(Some(buyer.clone()) != Some(seller.clone()), Error::<T>::NoBuySelf);
-			// 获取kitty价格
-			let price = KittiesShop::<T>::get(kitty_id).ok_or(Error::<T>::NotForSale)?;
-			// 获取买家账户余额
-			let buyer_balance = T::Currency::free_balance(&buyer);
-			// 获取需要质押的金额配置
-			let stake_amount = T::KittyStake::get();
-			// 检查买家的余额是否足够用于购买和质押
-			ensure!(buyer_balance > (price + stake_amount), Error::<T>::NotEnoughBalance);
-			// 获取要质押的数量
-			let stake_amount = T::KittyStake::get();
-			// 买家质押指定的资产数量
-			T::Currency::reserve(&buyer, stake_amount).map_err(|_| Error::<T>::NotEnoughBalance)?;
-			// 卖家解除质押数量
-			T::Currency::unreserve(&seller, stake_amount);
-			// 买家支付相应价格的token数给卖家
-			T::Currency::transfer(&buyer, &seller, price, ExistenceRequirement::KeepAlive)?;
-			// 更新kitty所有者
-			KittyOwner::<T>::insert(kitty_id, buyer.clone());
-			// 通告事件
-			Self::deposit_event(Event::KittyTransferred(seller, buyer, kitty_id));
-			Ok(().into())
+			Self::deposit_event(Event::KittyApproved(owner, delegate, kitty_id));
+			Ok(())
 		}
-	}
 
-	impl<T: Config> Pallet<T> {
-		// 获取一个256位的随机数 用作Kitty的DNA
-		fn random_value(sender: &T::AccountId) -> [u8; 16] {
-			let payload = (
-				T::Randomness::random_seed(),
-				&sender,
-				<frame_system::Pallet<T>>::extrinsic_index(), //获取当前交易在区块中的index，相当于nonce
-			);
-			payload.using_encoded(blake2_128)
-		}
-		// 根据ID获取kitty
-		fn kitty_of_id(kitty_id: T::KittyIndex) -> Result<Kitty, ()> {
-			match Self::kitties_info(kitty_id) {
-				Some(kitty) => Ok(kitty),
-				None => Err(()),
+		/// 授权或取消授权操作员转移自己的所有kitty
+		#[pallet::weight(T::WeightInfo::set_approval_for_all())]
+		pub fn set_approval_for_all(
+			origin: OriginFor<T>,
+			operator: T::AccountId,
+			approved: bool,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			if approved {
+				OperatorApprovals::<T>::insert(&sender, &operator, ());
+			} else {
+				OperatorApprovals::<T>::remove(&sender, &operator);
 			}
+
+			Self::deposit_event(Event::ApprovalForAll(sender, operator, approved));
+			Ok(())
 		}
 
-		// 质押并创建Kitty
-		fn new_kitty_with_stake(
-			sender: &T::AccountId,
-			dna: [u8; 16],
-		) -> DispatchResultWithPostInfo {
-			// 获取要质量的资产数量
-			let stake_amount = T::KittyStake::get();
+		/// 由拥有者、被授权账户或操作员把kitty转移给新的拥有者
+		#[pallet::weight(T::WeightInfo::transfer_from())]
+		pub fn transfer_from(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			new_owner: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(Self::is_approved_or_owner(&sender, &owner, kitty_id), Error::<T>::NotApproved);
 
-			// 开始质押指定数量的资产，失败则报余额不跢的错误
-			T::Currency::reserve(&sender, stake_amount)
-				.map_err(|_| Error::<T>::NotEnoughBalance)?;
+			Self::do_transfer(kitty_id, &owner, &new_owner)
+		}
 
-			let kitty_id = Self::next_kitty_id();
-			if kitty_id == T::KittyIndex::max_value() {
-				return Err(Error::<T>::KittyIdOverflow.into());
-			}
+		/// 把kitty赠送给接收人，接收人领取时才质押，领取前赠送人可以取消
+		#[pallet::weight(T::WeightInfo::gift())]
+		pub fn gift(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			recipient: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			// 验证操作者是否为拥有者
+			ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), Error::<T>::NotOwner);
+			Self::ensure_tradable(kitty_id)?;
 
-			// 生成新kitty实例
-			let kitty = Kitty { dna };
+			PendingGifts::<T>::insert(kitty_id, &recipient);
 
-			// 保存kitty实例与kittyid的对应关系
-			Kitties::<T>::insert(kitty_id, &kitty);
-			// 保存kittyid与所有者之间的对应关系
-			KittyOwner::<T>::insert(kitty_id, &sender);
+			Self::deposit_event(Event::KittyGiftOffered(sender, recipient, kitty_id));
+			Ok(())
+		}
 
-			// 获取最后一个kittyid，并自增加1
-			let next_kitty_id = kitty_id.checked_add(&(T::KittyIndex::from(1_u8))).unwrap();
-			// 保存最后一个kittyid
-			NextKittyId::<T>::set(next_kitty_id);
+		/// 取消尚未领取的赠送
+		#[pallet::weight(T::WeightInfo::cancel_gift())]
+		pub fn cancel_gift(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			// 验证操作者是否为拥有者
+			ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), Error::<T>::NotOwner);
+			ensure!(PendingGifts::<T>::contains_key(kitty_id), Error::<T>::NoPendingGift);
+
+			PendingGifts::<T>::remove(kitty_id);
+
+			Self::deposit_event(Event::KittyGiftCancelled(sender, kitty_id));
+			Ok(())
+		}
+
+		/// 接收人领取赠送的kitty，此时才由接收人质押
+		#[pallet::weight(T::WeightInfo::claim_gift())]
+		pub fn claim_gift(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let recipient = Self::pending_gifts(kitty_id).ok_or(Error::<T>::NoPendingGift)?;
+			ensure!(recipient == sender, Error::<T>::NotGiftRecipient);
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			Self::ensure_tradable(kitty_id)?;
+
+			// 接收人质押，赠送人解除质押
+			let stake_amount = T::KittyStake::get();
+			T::Currency::reserve(&sender, stake_amount)
+				.map_err(|_| Error::<T>::NotEnoughBalance)?;
+			T::Currency::unreserve(&owner, stake_amount);
+			// 保存新的拥有者关系，同时清除赠送记录
+			Self::change_owner(kitty_id, &owner, &sender);
+
+			Self::deposit_event(Event::KittyGiftClaimed(owner.clone(), sender.clone(), kitty_id));
+			Self::deposit_event(Event::KittyTransferred(owner, sender, kitty_id));
+			Ok(())
+		}
+
+		/// 对任意kitty报价，报价金额会被质押直到报价被接受、撤回或过期
+		#[pallet::weight(T::WeightInfo::make_offer())]
+		pub fn make_offer(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			amount: BalanceOf<T>,
+			expires_at: T::BlockNumber,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(owner != sender, Error::<T>::NoBuySelf);
+			ensure!(!Offers::<T>::contains_key(kitty_id, &sender), Error::<T>::OfferAlreadyExists);
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(expires_at > now, Error::<T>::InvalidOfferExpiry);
+
+			T::Currency::reserve(&sender, amount).map_err(|_| Error::<T>::NotEnoughBalance)?;
+			Offers::<T>::insert(kitty_id, &sender, KittyOffer { amount, expires_at });
+			OfferExpiries::<T>::insert(expires_at, (kitty_id, sender.clone()), ());
+			// 第一次有报价时才开始检查过期
+			if !NextOfferExpiryCheck::<T>::exists() {
+				NextOfferExpiryCheck::<T>::put(now);
+			}
+
+			Self::deposit_event(Event::OfferMade(sender, kitty_id, amount, expires_at));
+			Ok(())
+		}
+
+		/// 撤回报价并解除质押
+		#[pallet::weight(T::WeightInfo::withdraw_offer())]
+		pub fn withdraw_offer(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let offer = Self::remove_offer(kitty_id, &sender).ok_or(Error::<T>::NoOffer)?;
+			T::Currency::unreserve(&sender, offer.amount);
+
+			Self::deposit_event(Event::OfferWithdrawn(sender, kitty_id));
+			Ok(())
+		}
+
+		/// 拥有者接受报价：报价金额支付给拥有者，kitty转给报价人
+		#[pallet::weight(T::WeightInfo::accept_offer())]
+		pub fn accept_offer(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			bidder: T::AccountId,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), Error::<T>::NotOwner);
+			let offer = Self::offers(kitty_id, &bidder).ok_or(Error::<T>::NoOffer)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(now < offer.expires_at, Error::<T>::OfferExpired);
+			Self::ensure_tradable(kitty_id)?;
+			Self::remove_offer(kitty_id, &bidder);
+
+			// 质押的报价金额支付给拥有者和kitty的创作者
+			let (seller_amount, royalty) =
+				Self::pay_reserved(kitty_id, &bidder, &sender, offer.amount)?;
+			// 报价人质押，原拥有者解除质押
+			let stake_amount = T::KittyStake::get();
+			T::Currency::reserve(&bidder, stake_amount)
+				.map_err(|_| Error::<T>::NotEnoughBalance)?;
+			T::Currency::unreserve(&sender, stake_amount);
+			Self::change_owner(kitty_id, &sender, &bidder);
+
+			Self::deposit_event(Event::OfferAccepted(
+				sender.clone(),
+				bidder.clone(),
+				kitty_id,
+				offer.amount,
+			));
+			Self::deposit_event(Event::KittySold(
+				sender.clone(),
+				bidder.clone(),
+				kitty_id,
+				seller_amount,
+				royalty,
+			));
+			Self::deposit_event(Event::KittyTransferred(sender, bidder, kitty_id));
+			Ok(())
+		}
+
+		/// 甲方托管自己的kitty，向乙方发起交易，乙方需要托管对价
+		#[pallet::weight(T::WeightInfo::open_trade())]
+		pub fn open_trade(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			counterparty: T::AccountId,
+			consideration: TradeConsideration<BalanceOf<T>, T::KittyIndex>,
+			deadline: T::BlockNumber,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), Error::<T>::NotOwner);
+			ensure!(counterparty != sender, Error::<T>::NoBuySelf);
+			Self::ensure_tradable(kitty_id)?;
+			let now = <frame_system::Pallet<T>>::block_number();
+			ensure!(deadline > now, Error::<T>::InvalidTradeDeadline);
+
+			let trade_id = Self::next_trade_id();
+			let next_id = trade_id.checked_add(1).ok_or(Error::<T>::TradeIdOverflow)?;
+			TradeDeadlines::<T>::try_append(deadline, trade_id)
+				.map_err(|_| Error::<T>::TooManyTrades)?;
+			NextTradeId::<T>::put(next_id);
+			Self::lock_in_escrow(kitty_id, trade_id);
+			Trades::<T>::insert(
+				trade_id,
+				KittyTrade {
+					maker: sender.clone(),
+					kitty_id,
+					taker: counterparty.clone(),
+					consideration: consideration.clone(),
+					taker_locked: false,
+					maker_confirmed: false,
+					taker_confirmed: false,
+					deadline,
+				},
+			);
+
+			Self::deposit_event(Event::TradeOpened(
+				trade_id,
+				sender,
+				counterparty,
+				kitty_id,
+				consideration,
+				deadline,
+			));
+			Ok(())
+		}
+
+		/// 乙方托管对价：资金被质押，或另一只kitty被托管
+		#[pallet::weight(T::WeightInfo::lock_trade())]
+		pub fn lock_trade(origin: OriginFor<T>, trade_id: TradeId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut trade = Self::trades(trade_id).ok_or(Error::<T>::TradeNotFound)?;
+			ensure!(trade.taker == sender, Error::<T>::NotTradeParty);
+			ensure!(!trade.taker_locked, Error::<T>::TradeAlreadyLocked);
+
+			match trade.consideration {
+				TradeConsideration::Funds(amount) => {
+					T::Currency::reserve(&sender, amount)
+						.map_err(|_| Error::<T>::NotEnoughBalance)?;
+				},
+				TradeConsideration::Kitty(other_id) => {
+					ensure!(
+						Self::kitty_owner(other_id) == Some(sender.clone()),
+						Error::<T>::NotOwner
+					);
+					Self::ensure_tradable(other_id)?;
+					Self::lock_in_escrow(other_id, trade_id);
+				},
+			}
+			trade.taker_locked = true;
+			Trades::<T>::insert(trade_id, trade);
+
+			Self::deposit_event(Event::TradeLocked(trade_id, sender));
+			Ok(())
+		}
+
+		/// 交易双方确认交易，乙方托管对价后才能确认，双方都确认后立即原子交换
+		#[pallet::weight(T::WeightInfo::confirm_trade())]
+		pub fn confirm_trade(origin: OriginFor<T>, trade_id: TradeId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut trade = Self::trades(trade_id).ok_or(Error::<T>::TradeNotFound)?;
+			ensure!(trade.taker_locked, Error::<T>::TradeNotLocked);
+			let confirmed = if sender == trade.maker {
+				&mut trade.maker_confirmed
+			} else if sender == trade.taker {
+				&mut trade.taker_confirmed
+			} else {
+				return Err(Error::<T>::NotTradeParty.into())
+			};
+			ensure!(!*confirmed, Error::<T>::TradeAlreadyConfirmed);
+			*confirmed = true;
+			Self::deposit_event(Event::TradeConfirmed(trade_id, sender));
+
+			if trade.maker_confirmed && trade.taker_confirmed {
+				Self::remove_trade(trade_id, &trade);
+				Self::execute_trade(&trade)?;
+				Self::deposit_event(Event::TradeExecuted(trade_id));
+			} else {
+				Trades::<T>::insert(trade_id, trade);
+			}
+			Ok(())
+		}
+
+		/// 双方都确认之前，任意一方都可以取消交易，托管的kitty和资金原路退回
+		#[pallet::weight(T::WeightInfo::cancel_trade())]
+		pub fn cancel_trade(origin: OriginFor<T>, trade_id: TradeId) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let trade = Self::trades(trade_id).ok_or(Error::<T>::TradeNotFound)?;
+			ensure!(sender == trade.maker || sender == trade.taker, Error::<T>::NotTradeParty);
+
+			Self::remove_trade(trade_id, &trade);
+			Self::unwind_trade(&trade);
+
+			Self::deposit_event(Event::TradeCancelled(trade_id, sender));
+			Ok(())
+		}
+
+		/// 把kitty拆分为固定数量的份额，全部份额记在拥有者名下
+		#[pallet::weight(T::WeightInfo::fractionalize())]
+		pub fn fractionalize(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			shares: u32,
+			reserve_price: BalanceOf<T>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), Error::<T>::NotOwner);
+			Self::ensure_tradable(kitty_id)?;
+			ensure!(shares > 0, Error::<T>::InvalidShareCount);
+			// 保留价为 0 时任何人都可以免费买断
+			ensure!(!reserve_price.is_zero(), Error::<T>::InvalidReservePrice);
+			// 上一次买断的款项领取完之前，账本中还有旧的份额
+			ensure!(!Buyouts::<T>::contains_key(kitty_id), Error::<T>::BuyoutPending);
+
+			Self::withdraw_from_market(kitty_id);
+			Fractions::<T>::insert(kitty_id, KittyFraction { total_shares: shares, reserve_price });
+			KittyShares::<T>::insert(kitty_id, &sender, shares);
+
+			Self::deposit_event(Event::KittyFractionalized(
+				sender,
+				kitty_id,
+				shares,
+				reserve_price,
+			));
+			Ok(())
+		}
+
+		/// 转让kitty的份额，买断后转让的是领取买断款的权利
+		#[pallet::weight(T::WeightInfo::transfer_shares())]
+		pub fn transfer_shares(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			to: T::AccountId,
+			amount: u32,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(amount > 0, Error::<T>::InvalidShareCount);
+			let balance = Self::kitty_shares(kitty_id, &sender);
+			let remaining = balance.checked_sub(amount).ok_or(Error::<T>::NotEnoughShares)?;
+
+			if sender != to {
+				KittyShares::<T>::mutate(kitty_id, &to, |shares| *shares += amount);
+				if remaining == 0 {
+					KittyShares::<T>::remove(kitty_id, &sender);
+				} else {
+					KittyShares::<T>::insert(kitty_id, &sender, remaining);
+				}
+			}
+
+			Self::deposit_event(Event::SharesTransferred(sender, to, kitty_id, amount));
+			Ok(())
+		}
+
+		/// 持有全部份额的账户赎回kitty，份额随之销毁
+		#[pallet::weight(T::WeightInfo::redeem())]
+		pub fn redeem(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let fraction = Self::fractions(kitty_id).ok_or(Error::<T>::NotFractionalized)?;
+			ensure!(
+				Self::kitty_shares(kitty_id, &sender) == fraction.total_shares,
+				Error::<T>::NotEnoughShares
+			);
+
+			Fractions::<T>::remove(kitty_id);
+			KittyShares::<T>::remove(kitty_id, &sender);
+			Self::claim_fractionalized_kitty(kitty_id, &sender)?;
+
+			Self::deposit_event(Event::KittyRedeemed(sender, kitty_id));
+			Ok(())
+		}
+
+		/// 支付保留价买断kitty，买断款由买家质押，份额持有人用 `claim_buyout_proceeds` 领取
+		#[pallet::weight(T::WeightInfo::buyout())]
+		pub fn buyout(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let fraction = Self::fractions(kitty_id).ok_or(Error::<T>::NotFractionalized)?;
+
+			T::Currency::reserve(&sender, fraction.reserve_price)
+				.map_err(|_| Error::<T>::NotEnoughBalance)?;
+			Fractions::<T>::remove(kitty_id);
+			Buyouts::<T>::insert(
+				kitty_id,
+				KittyBuyout {
+					buyer: sender.clone(),
+					remaining_proceeds: fraction.reserve_price,
+					remaining_shares: fraction.total_shares,
+				},
+			);
+			Self::claim_fractionalized_kitty(kitty_id, &sender)?;
+
+			Self::deposit_event(Event::KittyBoughtOut(sender, kitty_id, fraction.reserve_price));
+			Ok(())
+		}
+
+		/// 份额持有人按份额比例领取买断款，份额随之销毁
+		#[pallet::weight(T::WeightInfo::claim_buyout_proceeds())]
+		pub fn claim_buyout_proceeds(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			let mut buyout = Self::buyouts(kitty_id).ok_or(Error::<T>::NoBuyout)?;
+			let shares = KittyShares::<T>::take(kitty_id, &sender);
+			ensure!(shares > 0, Error::<T>::NotEnoughShares);
+
+			// 最后一个领取的持有人拿走剩余的全部款项，避免舍入误差留在买家的质押中
+			let amount = if shares == buyout.remaining_shares {
+				buyout.remaining_proceeds
+			} else {
+				Perbill::from_rational(shares, buyout.remaining_shares) * buyout.remaining_proceeds
+			};
+			let remaining = T::Currency::repatriate_reserved(
+				&buyout.buyer,
+				&sender,
+				amount,
+				BalanceStatus::Free,
+			)?;
+			ensure!(remaining.is_zero(), Error::<T>::NotEnoughBalance);
+
+			buyout.remaining_shares -= shares;
+			buyout.remaining_proceeds -= amount;
+			if buyout.remaining_shares == 0 {
+				Buyouts::<T>::remove(kitty_id);
+			} else {
+				Buyouts::<T>::insert(kitty_id, buyout);
+			}
+
+			Self::deposit_event(Event::BuyoutProceedsClaimed(sender, kitty_id, shares, amount));
+			Ok(())
+		}
+
+		#[pallet::weight(T::WeightInfo::sell())]
+		pub fn sell(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			price: Option<BalanceOf<T>>,
+		) -> DispatchResultWithPostInfo {
+			let seller = ensure_signed(origin)?;
+			// 验证操作者是否为拥有者
+			ensure!(Self::kitty_owner(kitty_id) == Some(seller.clone()), Error::<T>::NotOwner);
+			Self::ensure_tradable(kitty_id)?;
+			// 给kitty设定价格，并保存关联有关系
+			KittiesShop::<T>::mutate_exists(kitty_id, |p| *p = Some(price));
+			// 以原生代币定价
+			ListingAssets::<T>::remove(kitty_id);
+
+			Self::deposit_event(Event::KittyInSell(seller, kitty_id, price));
+			Ok(().into())
+		}
+
+		/// 以指定的资产定价出售kitty，取消出售仍使用 `sell(kitty_id, None)`
+		#[pallet::weight(T::WeightInfo::sell_for_asset())]
+		pub fn sell_for_asset(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			asset_id: AssetIdOf<T>,
+			price: BalanceOf<T>,
+		) -> DispatchResultWithPostInfo {
+			let seller = ensure_signed(origin)?;
+			// 验证操作者是否为拥有者
+			ensure!(Self::kitty_owner(kitty_id) == Some(seller.clone()), Error::<T>::NotOwner);
+			Self::ensure_tradable(kitty_id)?;
+			// 资产必须已创建，不存在的资产最小余额为0
+			ensure!(
+				!<T::Assets as fungibles::Inspect<_>>::minimum_balance(asset_id).is_zero(),
+				Error::<T>::UnknownAsset
+			);
+			KittiesShop::<T>::insert(kitty_id, Some(price));
+			ListingAssets::<T>::insert(kitty_id, asset_id);
+
+			Self::deposit_event(Event::KittyInSellForAsset(seller, kitty_id, asset_id, price));
+			Ok(().into())
+		}
+
+		#[pallet::weight(T::WeightInfo::buy())]
+		pub fn buy(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResultWithPostInfo {
+			let buyer = ensure_signed(origin)?;
+			// 根据ID获取kitty所有者
+			let seller = KittyOwner::<T>::get(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			// 验证购买者是否为拥有者
+			ensure!(Some(buyer.clone()) != Some(seller.clone()), Error::<T>::NoBuySelf);
+			// 获取kitty价格
+			let price = KittiesShop::<T>::get(kitty_id).ok_or(Error::<T>::NotForSale)?;
+			Self::ensure_tradable(kitty_id)?;
+			// 获取定价使用的资产，None 为原生代币
+			let asset = Self::listing_assets(kitty_id);
+			// 获取买家账户余额
+			let buyer_balance = T::Currency::free_balance(&buyer);
+			// 获取需要质押的金额配置
+			let stake_amount = T::KittyStake::get();
+			// 检查买家的余额是否足够用于购买和质押，质押始终使用原生代币
+			match asset {
+				Some(asset_id) => {
+					ensure!(buyer_balance > stake_amount, Error::<T>::NotEnoughBalance);
+					ensure!(
+						<T::Assets as fungibles::Inspect<_>>::reducible_balance(
+							asset_id, &buyer, true
+						) >= price,
+						Error::<T>::NotEnoughBalance
+					);
+				},
+				None => {
+					ensure!(buyer_balance > (price + stake_amount), Error::<T>::NotEnoughBalance)
+				},
+			}
+			// 获取要质押的数量
+			let stake_amount = T::KittyStake::get();
+			// 买家质押指定的资产数量
+			T::Currency::reserve(&buyer, stake_amount).map_err(|_| Error::<T>::NotEnoughBalance)?;
+			// 卖家解除质押数量
+			T::Currency::unreserve(&seller, stake_amount);
+			// 买家先支付版税给kitty的创作者，其余支付给卖家
+			let mut royalty = Zero::zero();
+			if let Some((creator, amount)) = Self::royalty_of(kitty_id, &seller, price) {
+				// 创作者账户已不存在且版税低于最低余额时无法支付，全部付给卖家
+				if Self::pay(asset, &buyer, &creator, amount).is_ok() {
+					royalty = amount;
+					Self::deposit_event(Event::RoyaltyPaid(creator, kitty_id, amount));
+				}
+			}
+			let seller_amount = price - royalty;
+			Self::pay(asset, &buyer, &seller, seller_amount)?;
+			// 更新kitty所有者
+			Self::change_owner(kitty_id, &seller, &buyer);
+			// 通告事件
+			Self::deposit_event(Event::KittySold(
+				seller.clone(),
+				buyer.clone(),
+				kitty_id,
+				seller_amount,
+				royalty,
+			));
+			Self::deposit_event(Event::KittyTransferred(seller, buyer, kitty_id));
+			Ok(().into())
+		}
+
+		/// 销毁kitty并解除质押
+		#[pallet::weight(T::WeightInfo::burn())]
+		pub fn burn(origin: OriginFor<T>, kitty_id: T::KittyIndex) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			// 验证操作者是否为拥有者
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(owner == sender, Error::<T>::NotOwner);
+			Self::ensure_tradable(kitty_id)?;
+			// 繁殖锁定期内的kitty不能销毁
+			if let Some(locked_until) = Self::breeding_locks(kitty_id) {
+				let now = <frame_system::Pallet<T>>::block_number();
+				ensure!(now >= locked_until, Error::<T>::KittyBreedingLocked);
+			}
+
+			// 删除kitty及相关的所有者、上架信息
+			Kitties::<T>::remove(kitty_id);
+			KittyOwner::<T>::remove(kitty_id);
+			OwnedKitties::<T>::remove(&sender, kitty_id);
+			KittiesShop::<T>::remove(kitty_id);
+			ListingAssets::<T>::remove(kitty_id);
+			BreedingLocks::<T>::remove(kitty_id);
+			KittyApprovals::<T>::remove(kitty_id);
+			PendingGifts::<T>::remove(kitty_id);
+			// 清除名字和元数据URI，并退还押金
+			KittyNames::<T>::remove(kitty_id);
+			KittyMetadataUris::<T>::remove(kitty_id);
+			if let Some((depositor, deposit)) = MetadataDeposits::<T>::take(kitty_id) {
+				T::Currency::unreserve(&depositor, deposit);
+			}
+			KittyAssets::<T>::remove(kitty_id);
+			KittyCreators::<T>::remove(kitty_id);
+			// 销毁的kitty不再需要计算资产评分
+			offchain_index::clear(&Self::derived_key(kitty_id));
+			// 解除创建时的质押
+			T::Currency::unreserve(&sender, T::KittyStake::get());
+
+			Self::deposit_event(Event::KittyBurned(sender, kitty_id));
+			Ok(())
+		}
+
+		/// 设置kitty的名字，名字为空时清除名字
+		#[pallet::weight(T::WeightInfo::set_name())]
+		pub fn set_name(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			name: Vec<u8>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			// 验证操作者是否为拥有者
+			ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), Error::<T>::NotOwner);
+			// 检查名字是否超出最大长度
+			let bounded_name = BoundedVec::<u8, T::MaxNameLength>::try_from(name.clone())
+				.map_err(|_| Error::<T>::NameTooLong)?;
+
+			let uri_len = Self::kitty_metadata_uris(kitty_id).map_or(0, |uri| uri.len());
+			Self::update_metadata_deposit(kitty_id, &sender, bounded_name.len() + uri_len)?;
+
+			if bounded_name.is_empty() {
+				KittyNames::<T>::remove(kitty_id);
+			} else {
+				KittyNames::<T>::insert(kitty_id, bounded_name);
+			}
+
+			Self::deposit_event(Event::KittyNamed(sender, kitty_id, name));
+			Ok(())
+		}
+
+		/// 设置kitty的元数据URI，传入 None 时清除
+		#[pallet::weight(T::WeightInfo::set_metadata_uri())]
+		pub fn set_metadata_uri(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			uri: Option<Vec<u8>>,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			// 验证操作者是否为拥有者
+			ensure!(Self::kitty_owner(kitty_id) == Some(sender.clone()), Error::<T>::NotOwner);
+			// 检查URI是否超出最大长度
+			let bounded_uri = uri
+				.clone()
+				.map(BoundedVec::<u8, T::MaxMetadataUriLength>::try_from)
+				.transpose()
+				.map_err(|_| Error::<T>::MetadataUriTooLong)?;
+
+			let name_len = Self::kitty_names(kitty_id).map_or(0, |name| name.len());
+			let uri_len = bounded_uri.as_ref().map_or(0, |uri| uri.len());
+			Self::update_metadata_deposit(kitty_id, &sender, name_len + uri_len)?;
+
+			match bounded_uri {
+				Some(bounded_uri) => KittyMetadataUris::<T>::insert(kitty_id, bounded_uri),
+				None => KittyMetadataUris::<T>::remove(kitty_id),
+			}
+
+			Self::deposit_event(Event::KittyMetadataUriSet(sender, kitty_id, uri));
+			Ok(())
+		}
+
+		/// 提交kitty的资产评分（签名交易），只有资产评分提交者可以调用
+		#[pallet::weight(T::WeightInfo::update_kitty_asset())]
+		pub fn update_kitty_asset(
+			origin: OriginFor<T>,
+			kitty_id: T::KittyIndex,
+			asset: u32,
+		) -> DispatchResult {
+			let sender = ensure_signed(origin)?;
+			ensure!(Self::is_asset_authority(&sender), Error::<T>::NotAssetAuthority);
+			Self::set_kitty_asset(kitty_id, asset)?;
+
+			Self::deposit_event(Event::KittyAssetUpdated(Some(sender), kitty_id, asset));
+			Ok(())
+		}
+
+		/// 提交kitty的资产评分（带签名payload的无签名交易），由链下工作机提交，不需要手续费
+		#[pallet::weight(T::WeightInfo::update_kitty_asset_unsigned_with_signed_payload())]
+		pub fn update_kitty_asset_unsigned_with_signed_payload(
+			origin: OriginFor<T>,
+			payload: KittyAssetPayload<T::Public, T::KittyIndex>,
+			_signature: T::Signature,
+		) -> DispatchResult {
+			ensure_none(origin)?;
+			// 签名已经在 `validate_unsigned` 中验证过
+			let KittyAssetPayload { kitty_id, asset, public } = payload;
+			ensure!(
+				Self::is_asset_authority(&public.into_account()),
+				Error::<T>::NotAssetAuthority
+			);
+			Self::set_kitty_asset(kitty_id, asset)?;
+
+			Self::deposit_event(Event::KittyAssetUpdated(None, kitty_id, asset));
+			Ok(())
+		}
+
+		/// 把账户加入资产评分提交者集合
+		#[pallet::weight(T::WeightInfo::add_asset_authority())]
+		pub fn add_asset_authority(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::AssetAuthorityOrigin::ensure_origin(origin)?;
+			AssetAuthorities::<T>::try_mutate(|authorities| -> DispatchResult {
+				ensure!(!authorities.contains(&who), Error::<T>::AlreadyAssetAuthority);
+				authorities
+					.try_push(who.clone())
+					.map_err(|_| Error::<T>::TooManyAssetAuthorities)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::AssetAuthorityAdded(who));
+			Ok(())
+		}
+
+		/// 把账户移出资产评分提交者集合，已经提交的评分仍然有效
+		#[pallet::weight(T::WeightInfo::remove_asset_authority())]
+		pub fn remove_asset_authority(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::AssetAuthorityOrigin::ensure_origin(origin)?;
+			AssetAuthorities::<T>::try_mutate(|authorities| -> DispatchResult {
+				let index = authorities
+					.iter()
+					.position(|authority| authority == &who)
+					.ok_or(Error::<T>::NotAssetAuthority)?;
+				authorities.remove(index);
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::AssetAuthorityRemoved(who));
+			Ok(())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			match call {
+				Call::update_kitty_asset_unsigned_with_signed_payload {
+					ref payload,
+					ref signature,
+				} => {
+					if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
+						return InvalidTransaction::BadProof.into()
+					}
+					// 只接受资产评分提交者的密钥，避免任何人用临时密钥免费写入评分
+					if !Self::is_asset_authority(&payload.public.clone().into_account()) {
+						return InvalidTransaction::BadSigner.into()
+					}
+					// kitty 已经销毁
+					if !Kitties::<T>::contains_key(payload.kitty_id) {
+						return InvalidTransaction::Stale.into()
+					}
+					ValidTransaction::with_tag_prefix("kitty-asset")
+						.priority(UNSIGNED_TXS_PRIORITY)
+						.and_provides((payload.kitty_id, payload.asset))
+						.longevity(5)
+						.propagate(true)
+						.build()
+				},
+				_ => InvalidTransaction::Call.into(),
+			}
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		// 获取一个256位的随机数 用作Kitty的DNA
+		fn random_value(sender: &T::AccountId) -> [u8; 16] {
+			let payload = (
+				T::Randomness::random_seed(),
+				&sender,
+				<frame_system::Pallet<T>>::extrinsic_index(), //获取当前交易在区块中的index，相当于nonce
+			);
+			payload.using_encoded(blake2_128)
+		}
+		// 用提交时还不确定的目标区块哈希和用户的 salt 生成随机数，
+		// 出块者无法预先知道 salt，提交者也无法预先知道区块哈希。
+		// 目标区块哈希已超出 `BlockHashCount` 被清理时返回 None，不能退回到 `random_value`，
+		// 否则提交者可以等到哈希被清理后重新随机
+		fn committed_random_value(
+			sender: &T::AccountId,
+			salt: &T::Hash,
+			reveal_at: T::BlockNumber,
+		) -> Option<[u8; 16]> {
+			let target_hash = <frame_system::Pallet<T>>::block_hash(reveal_at);
+			if target_hash == T::Hash::default() {
+				return None
+			}
+			Some((target_hash, salt, &sender).using_encoded(blake2_128))
+		}
+
+		// 根据父母的DNA和随机数生成子kitty的DNA
+		fn breed_dna(
+			kitty_id_1: T::KittyIndex,
+			kitty_id_2: T::KittyIndex,
+			selector: &[u8; 16],
+		) -> Result<[u8; 16], DispatchError> {
+			// 验证父母是不是同一个kitty
+			ensure!(kitty_id_1 != kitty_id_2, Error::<T>::SameParentId);
+
+			// 检查kitty_id是否存在且有效
+			let kitty_1 = Self::kitty_of_id(kitty_id_1).map_err(|_| Error::<T>::InvalidKittyId)?;
+			let kitty_2 = Self::kitty_of_id(kitty_id_2).map_err(|_| Error::<T>::InvalidKittyId)?;
+			// 碎片化的kitty不能繁殖
+			Self::ensure_not_fractionalized(kitty_id_1)?;
+			Self::ensure_not_fractionalized(kitty_id_2)?;
+
+			// 按配置的混合策略，由父母的基因与随机数得到子Kitty的完整基因
+			Ok(T::DnaMixer::mix(&kitty_1.dna, &kitty_2.dna, selector))
+		}
+
+		// 繁殖者必须是父母的拥有者或被授权者
+		fn ensure_can_breed(who: &T::AccountId, kitty_id: T::KittyIndex) -> DispatchResult {
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			ensure!(Self::is_approved_or_owner(who, &owner, kitty_id), Error::<T>::NotOwner);
+			Ok(())
+		}
+
+		// 质押并保存DNA请求
+		fn commit_dna_request(
+			sender: &T::AccountId,
+			commitment: T::Hash,
+			parents: Option<(T::KittyIndex, T::KittyIndex)>,
+		) -> DispatchResult {
+			ensure!(!DnaRequests::<T>::contains_key(sender), Error::<T>::RequestAlreadyPending);
+
+			// 提交时就质押，避免不满意结果时放弃揭示反复重来
+			T::Currency::reserve(sender, T::KittyStake::get())
+				.map_err(|_| Error::<T>::NotEnoughBalance)?;
+
+			let reveal_at = <frame_system::Pallet<T>>::block_number() + T::RevealDelay::get();
+			DnaRequests::<T>::insert(sender, DnaRequest { commitment, reveal_at, parents });
+
+			Self::deposit_event(Event::DnaRequested(sender.clone(), reveal_at));
+			Ok(())
+		}
+
+		// 揭示或放弃繁殖请求后解除父母的锁定
+		fn release_breeding_parents(parents: Option<(T::KittyIndex, T::KittyIndex)>) {
+			if let Some((kitty_id_1, kitty_id_2)) = parents {
+				for kitty_id in [kitty_id_1, kitty_id_2] {
+					PendingBreedingParents::<T>::mutate_exists(kitty_id, |pending| {
+						*pending = pending.and_then(|n| n.checked_sub(1)).filter(|n| *n > 0)
+					});
+				}
+			}
+		}
+
+		// 繁殖后记录族谱并锁定父母kitty，族谱在kitty销毁后仍然保留
+		fn record_breeding(
+			child_id: T::KittyIndex,
+			kitty_id_1: T::KittyIndex,
+			kitty_id_2: T::KittyIndex,
+		) {
+			KittyParents::<T>::insert(child_id, (kitty_id_1, kitty_id_2));
+			KittyChildren::<T>::insert(kitty_id_1, child_id, ());
+			KittyChildren::<T>::insert(kitty_id_2, child_id, ());
+
+			let locked_until =
+				<frame_system::Pallet<T>>::block_number() + T::BreedingLockPeriod::get();
+			BreedingLocks::<T>::insert(kitty_id_1, locked_until);
+			BreedingLocks::<T>::insert(kitty_id_2, locked_until);
+		}
+
+		// 更新kitty所有者及索引，原有的上架信息和授权随所有权变更失效
+		fn change_owner(kitty_id: T::KittyIndex, from: &T::AccountId, to: &T::AccountId) {
+			KittyOwner::<T>::insert(kitty_id, to);
+			OwnedKitties::<T>::remove(from, kitty_id);
+			OwnedKitties::<T>::insert(to, kitty_id, ());
+			KittiesShop::<T>::remove(kitty_id);
+			ListingAssets::<T>::remove(kitty_id);
+			KittyApprovals::<T>::remove(kitty_id);
+			PendingGifts::<T>::remove(kitty_id);
+		}
+
+		// 转移kitty，原拥有者的质押直接转为新拥有者的质押，不需要新拥有者额外出资
+		fn do_transfer(
+			kitty_id: T::KittyIndex,
+			from: &T::AccountId,
+			to: &T::AccountId,
+		) -> DispatchResult {
+			Self::ensure_tradable(kitty_id)?;
+			let stake_amount = T::KittyStake::get();
+			// 新拥有者账户必须已存在，才能接收质押
+			let remaining =
+				T::Currency::repatriate_reserved(from, to, stake_amount, BalanceStatus::Reserved)
+					.map_err(|_| Error::<T>::RecipientNotExist)?;
+			ensure!(remaining.is_zero(), Error::<T>::NotEnoughBalance);
+			// 保存新的拥有者关系
+			Self::change_owner(kitty_id, from, to);
+
+			Self::deposit_event(Event::KittyTransferred(from.clone(), to.clone(), kitty_id));
+			Ok(())
+		}
+
+		// 用原生代币或指定的资产付款，付款方账户保持存活
+		fn pay(
+			asset: Option<AssetIdOf<T>>,
+			from: &T::AccountId,
+			to: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> DispatchResult {
+			match asset {
+				Some(asset_id) => <T::Assets as fungibles::Transfer<_>>::transfer(
+					asset_id, from, to, amount, true,
+				)
+				.map(|_| ()),
+				None => T::Currency::transfer(from, to, amount, ExistenceRequirement::KeepAlive),
+			}
+		}
+
+		// 计算二次销售的版税，创作者出售自己的kitty时不收取
+		fn royalty_of(
+			kitty_id: T::KittyIndex,
+			seller: &T::AccountId,
+			price: BalanceOf<T>,
+		) -> Option<(T::AccountId, BalanceOf<T>)> {
+			let creator = Self::kitty_creators(kitty_id)?;
+			if &creator == seller {
+				return None
+			}
+			let royalty = T::RoyaltyRate::get() * price;
+			if royalty.is_zero() {
+				None
+			} else {
+				Some((creator, royalty))
+			}
+		}
+
+		// 从买家质押的资金中先支付版税给kitty的创作者，其余支付给卖家，返回 (卖家所得, 版税)
+		fn pay_reserved(
+			kitty_id: T::KittyIndex,
+			buyer: &T::AccountId,
+			seller: &T::AccountId,
+			amount: BalanceOf<T>,
+		) -> Result<(BalanceOf<T>, BalanceOf<T>), DispatchError> {
+			let mut royalty = Zero::zero();
+			if let Some((creator, royalty_amount)) = Self::royalty_of(kitty_id, seller, amount) {
+				// 创作者账户已不存在时无法支付，全部付给卖家
+				if let Ok(remaining) = T::Currency::repatriate_reserved(
+					buyer,
+					&creator,
+					royalty_amount,
+					BalanceStatus::Free,
+				) {
+					royalty = royalty_amount - remaining;
+					Self::deposit_event(Event::RoyaltyPaid(creator, kitty_id, royalty));
+				}
+			}
+			let seller_amount = amount - royalty;
+			let remaining = T::Currency::repatriate_reserved(
+				buyer,
+				seller,
+				seller_amount,
+				BalanceStatus::Free,
+			)?;
+			ensure!(remaining.is_zero(), Error::<T>::NotEnoughBalance);
+			Ok((seller_amount, royalty))
+		}
+
+		// 托管中、已碎片化或等待揭示繁殖结果的kitty不能转移、出售、赠送或销毁
+		fn ensure_tradable(kitty_id: T::KittyIndex) -> DispatchResult {
+			ensure!(!EscrowedKitties::<T>::contains_key(kitty_id), Error::<T>::KittyInEscrow);
+			ensure!(
+				!PendingBreedingParents::<T>::contains_key(kitty_id),
+				Error::<T>::KittyBreedingPending
+			);
+			Self::ensure_not_fractionalized(kitty_id)
+		}
+
+		fn ensure_not_fractionalized(kitty_id: T::KittyIndex) -> DispatchResult {
+			ensure!(!Fractions::<T>::contains_key(kitty_id), Error::<T>::KittyFractionalized);
+			Ok(())
+		}
+
+		// 撤下kitty的上架信息和待领取的赠送
+		fn withdraw_from_market(kitty_id: T::KittyIndex) {
+			KittiesShop::<T>::remove(kitty_id);
+			ListingAssets::<T>::remove(kitty_id);
+			PendingGifts::<T>::remove(kitty_id);
+		}
+
+		// 托管kitty
+		fn lock_in_escrow(kitty_id: T::KittyIndex, trade_id: TradeId) {
+			EscrowedKitties::<T>::insert(kitty_id, trade_id);
+			Self::withdraw_from_market(kitty_id);
+		}
+
+		// 赎回或买断后把kitty转给新的拥有者，调用前需要先删除碎片化记录
+		fn claim_fractionalized_kitty(
+			kitty_id: T::KittyIndex,
+			who: &T::AccountId,
+		) -> DispatchResult {
+			let owner = Self::kitty_owner(kitty_id).ok_or(Error::<T>::InvalidKittyId)?;
+			if &owner != who {
+				Self::do_transfer(kitty_id, &owner, who)?;
+			}
+			Ok(())
+		}
+
+		// 删除交易及其截止区块索引
+		fn remove_trade(
+			trade_id: TradeId,
+			trade: &KittyTrade<T::AccountId, BalanceOf<T>, T::KittyIndex, T::BlockNumber>,
+		) {
+			Trades::<T>::remove(trade_id);
+			TradeDeadlines::<T>::mutate(trade.deadline, |ids| ids.retain(|id| *id != trade_id));
+		}
+
+		// 原子交换：乙方的对价交给甲方，甲方的kitty交给乙方，kitty的质押随kitty转移
+		fn execute_trade(
+			trade: &KittyTrade<T::AccountId, BalanceOf<T>, T::KittyIndex, T::BlockNumber>,
+		) -> DispatchResult {
+			EscrowedKitties::<T>::remove(trade.kitty_id);
+			match trade.consideration {
+				TradeConsideration::Funds(amount) => {
+					let (seller_amount, royalty) =
+						Self::pay_reserved(trade.kitty_id, &trade.taker, &trade.maker, amount)?;
+					Self::deposit_event(Event::KittySold(
+						trade.maker.clone(),
+						trade.taker.clone(),
+						trade.kitty_id,
+						seller_amount,
+						royalty,
+					));
+				},
+				TradeConsideration::Kitty(other_id) => {
+					EscrowedKitties::<T>::remove(other_id);
+					Self::do_transfer(other_id, &trade.taker, &trade.maker)?;
+				},
+			}
+			Self::do_transfer(trade.kitty_id, &trade.maker, &trade.taker)
+		}
+
+		// 解除托管：kitty解除托管，乙方质押的资金退回
+		fn unwind_trade(
+			trade: &KittyTrade<T::AccountId, BalanceOf<T>, T::KittyIndex, T::BlockNumber>,
+		) {
+			EscrowedKitties::<T>::remove(trade.kitty_id);
+			if trade.taker_locked {
+				match trade.consideration {
+					TradeConsideration::Funds(amount) => {
+						T::Currency::unreserve(&trade.taker, amount);
+					},
+					TradeConsideration::Kitty(other_id) => EscrowedKitties::<T>::remove(other_id),
+				}
+			}
+		}
+
+		// 解除到达截止区块仍未完成的交易
+		fn expire_trades(now: T::BlockNumber) -> Weight {
+			let trade_ids = TradeDeadlines::<T>::take(now);
+			for trade_id in trade_ids.iter() {
+				if let Some(trade) = Trades::<T>::take(trade_id) {
+					Self::unwind_trade(&trade);
+					Self::deposit_event(Event::TradeExpired(*trade_id));
+				}
+			}
+			// 读写截止区块索引，每个交易读写交易、托管的kitty和质押账户
+			T::DbWeight::get().reads_writes(1, 1).saturating_add(
+				T::DbWeight::get().reads_writes(2, 4).saturating_mul(trade_ids.len() as Weight),
+			)
+		}
+
+		// 删除报价及其过期索引
+		fn remove_offer(
+			kitty_id: T::KittyIndex,
+			bidder: &T::AccountId,
+		) -> Option<KittyOffer<BalanceOf<T>, T::BlockNumber>> {
+			let offer = Offers::<T>::take(kitty_id, bidder)?;
+			OfferExpiries::<T>::remove(offer.expires_at, (kitty_id, bidder.clone()));
+			Some(offer)
+		}
+
+		// 在权重限制内清理已经过期的报价并退还质押，返回实际使用的权重
+		fn clean_expired_offers(now: T::BlockNumber, limit: Weight) -> Weight {
+			let db_weight = T::DbWeight::get();
+			// 读写检查游标
+			let mut used = db_weight.reads_writes(1, 1);
+			if used > limit {
+				return 0
+			}
+			let mut next_check = match NextOfferExpiryCheck::<T>::get() {
+				Some(next_check) => next_check,
+				None => return db_weight.reads(1),
+			};
+
+			// 每个区块读取一次过期索引，每个报价读写报价、索引和质押账户
+			let per_block = db_weight.reads(1);
+			let per_offer = db_weight.reads_writes(2, 3);
+			while next_check <= now {
+				if used.saturating_add(per_block) > limit {
+					break
+				}
+				used = used.saturating_add(per_block);
+
+				let mut expired = Vec::new();
+				let mut finished = true;
+				for key in OfferExpiries::<T>::iter_key_prefix(next_check) {
+					if used.saturating_add(per_offer) > limit {
+						finished = false;
+						break
+					}
+					used = used.saturating_add(per_offer);
+					expired.push(key);
+				}
+				for (kitty_id, bidder) in expired {
+					OfferExpiries::<T>::remove(next_check, (kitty_id, bidder.clone()));
+					if let Some(offer) = Offers::<T>::take(kitty_id, &bidder) {
+						T::Currency::unreserve(&bidder, offer.amount);
+						Self::deposit_event(Event::OfferExpired(bidder, kitty_id));
+					}
+				}
+				// 本区块的过期报价没有处理完，下次继续
+				if !finished {
+					break
+				}
+				next_check = next_check + One::one();
+			}
+			NextOfferExpiryCheck::<T>::put(next_check);
+			used
+		}
+
+		// 检查账户是否为拥有者、单个kitty的被授权账户或拥有者的操作员
+		fn is_approved_or_owner(
+			who: &T::AccountId,
+			owner: &T::AccountId,
+			kitty_id: T::KittyIndex,
+		) -> bool {
+			who == owner ||
+				Self::kitty_approvals(kitty_id).as_ref() == Some(who) ||
+				OperatorApprovals::<T>::contains_key(owner, who)
+		}
+
+		// 按名字和元数据URI的总字节数重新计算押金，由当前拥有者质押，退还之前质押人的押金
+		fn update_metadata_deposit(
+			kitty_id: T::KittyIndex,
+			owner: &T::AccountId,
+			total_bytes: usize,
+		) -> DispatchResult {
+			let deposit =
+				T::MetadataDepositPerByte::get() * BalanceOf::<T>::from(total_bytes as u32);
+			if !deposit.is_zero() {
+				T::Currency::reserve(owner, deposit).map_err(|_| Error::<T>::NotEnoughBalance)?;
+			}
+			if let Some((depositor, old_deposit)) = MetadataDeposits::<T>::take(kitty_id) {
+				T::Currency::unreserve(&depositor, old_deposit);
+			}
+			if !deposit.is_zero() {
+				MetadataDeposits::<T>::insert(kitty_id, (owner.clone(), deposit));
+			}
+			Ok(())
+		}
+
+		/// 查询kitty的完整信息，包括DNA、拥有者、名字、元数据URI和售价
+		pub fn kitty_info(
+			kitty_id: T::KittyIndex,
+		) -> Option<KittyInfo<T::AccountId, BalanceOf<T>, AssetIdOf<T>>> {
+			let kitty = Self::kitties_info(kitty_id)?;
+			let owner = Self::kitty_owner(kitty_id)?;
+			Some(KittyInfo {
+				dna: kitty.dna,
+				owner,
+				creator: Self::kitty_creators(kitty_id),
+				name: Self::kitty_names(kitty_id).map(|name| name.into_inner()).unwrap_or_default(),
+				metadata_uri: Self::kitty_metadata_uris(kitty_id).map(|uri| uri.into_inner()),
+				price: Self::kitties_list_for_sales(kitty_id),
+				price_asset: Self::listing_assets(kitty_id),
+				asset: Self::kitty_assets(kitty_id),
+			})
+		}
+
+		/// 分页查询正在出售的kitty、价格及计价资产，资产为 `None` 表示以原生代币计价
+		pub fn kitties_for_sale(
+			page: u32,
+		) -> Vec<(T::KittyIndex, BalanceOf<T>, Option<AssetIdOf<T>>)> {
+			KittiesShop::<T>::iter()
+				.filter_map(|(kitty_id, price)| {
+					price.map(|price| (kitty_id, price, Self::listing_assets(kitty_id)))
+				})
+				.skip(page.saturating_mul(QUERY_PAGE_SIZE) as usize)
+				.take(QUERY_PAGE_SIZE as usize)
+				.collect()
+		}
+
+		/// 查询kitty的售价及计价资产
+		pub fn price_of(kitty_id: T::KittyIndex) -> Option<(BalanceOf<T>, Option<AssetIdOf<T>>)> {
+			Self::kitties_list_for_sales(kitty_id)
+				.map(|price| (price, Self::listing_assets(kitty_id)))
+		}
+
+		/// 按代查询kitty的祖先，最多查询 `MAX_ANCESTOR_DEPTH` 代
+		pub fn ancestors(kitty_id: T::KittyIndex, depth: u32) -> Vec<T::KittyIndex> {
+			let mut ancestors = Vec::new();
+			let mut generation = Vec::from([kitty_id]);
+			for _ in 0..depth.min(MAX_ANCESTOR_DEPTH) {
+				let mut parents = Vec::new();
+				for (parent_1, parent_2) in generation.iter().filter_map(Self::kitty_parents) {
+					for parent in [parent_1, parent_2] {
+						if !ancestors.contains(&parent) {
+							ancestors.push(parent);
+							parents.push(parent);
+						}
+					}
+				}
+				if parents.is_empty() {
+					break
+				}
+				generation = parents;
+			}
+			ancestors
+		}
+
+		/// 按代分页查询kitty的后代，遍历的数量不超过已请求的页数
+		pub fn descendants(kitty_id: T::KittyIndex, page: u32) -> Vec<T::KittyIndex> {
+			let start = page.saturating_mul(QUERY_PAGE_SIZE) as usize;
+			let limit = start.saturating_add(QUERY_PAGE_SIZE as usize);
+
+			let mut descendants: Vec<T::KittyIndex> = Vec::new();
+			let mut next = 0;
+			let mut current = kitty_id;
+			'outer: loop {
+				for child in KittyChildren::<T>::iter_key_prefix(current) {
+					if descendants.len() >= limit {
+						break 'outer
+					}
+					if !descendants.contains(&child) {
+						descendants.push(child);
+					}
+				}
+				match descendants.get(next) {
+					Some(child) => current = *child,
+					None => break,
+				}
+				next += 1;
+			}
+			descendants.into_iter().skip(start).collect()
+		}
+
+		// 根据ID获取kitty
+		fn kitty_of_id(kitty_id: T::KittyIndex) -> Result<Kitty, ()> {
+			match Self::kitties_info(kitty_id) {
+				Some(kitty) => Ok(kitty),
+				None => Err(()),
+			}
+		}
+
+		// 质押并创建Kitty
+		fn new_kitty_with_stake(
+			sender: &T::AccountId,
+			dna: [u8; 16],
+		) -> DispatchResultWithPostInfo {
+			// 获取要质量的资产数量
+			let stake_amount = T::KittyStake::get();
+
+			// 开始质押指定数量的资产，失败则报余额不跢的错误
+			T::Currency::reserve(&sender, stake_amount)
+				.map_err(|_| Error::<T>::NotEnoughBalance)?;
+
+			Self::mint_kitty(sender, dna)?;
+			Ok(().into())
+		}
+
+		// 创建Kitty并保存所有者关系，调用者需确保已完成质押
+		fn mint_kitty(sender: &T::AccountId, dna: [u8; 16]) -> DispatchResult {
+			let kitty_id = Self::next_kitty_id();
+			if kitty_id == T::KittyIndex::max_value() {
+				return Err(Error::<T>::KittyIdOverflow.into());
+			}
+
+			// 生成新kitty实例
+			let kitty = Kitty { dna };
+
+			// 保存kitty实例与kittyid的对应关系
+			Kitties::<T>::insert(kitty_id, &kitty);
+			// 保存kittyid与所有者之间的对应关系
+			KittyOwner::<T>::insert(kitty_id, &sender);
+			OwnedKitties::<T>::insert(&sender, kitty_id, ());
+			KittyCreators::<T>::insert(kitty_id, &sender);
+			// 写入链下索引，由链下工作机计算资产评分
+			Self::save_kitty_to_indexing(kitty_id, dna);
+
+			// 获取最后一个kittyid，并自增加1
+			let next_kitty_id = kitty_id.checked_add(&(T::KittyIndex::from(1_u8))).unwrap();
+			// 保存最后一个kittyid
+			NextKittyId::<T>::set(next_kitty_id);
 
 			//通报事件
 			Self::deposit_event(Event::KittyCreatedAndBreed(sender.clone(), kitty_id));
 
-			Ok(().into())
+			Ok(())
 		}
 	}
 
 	impl<T: Config> Pallet<T> {
-		fn derived_key(block_number: T::BlockNumber) -> Vec<u8> {
-			block_number.using_encoded(|encoded_bn| {
+		// 链下索引的key：前缀 + kitty id
+		fn derived_key(kitty_id: T::KittyIndex) -> Vec<u8> {
+			kitty_id.using_encoded(|encoded_id| {
 				ONCHAIN_INDEX_KEY
-					.clone()
-					.into_iter()
-					.chain(b"/".into_iter())
-					.chain(encoded_bn)
+					.iter()
+					.chain(b"/".iter())
+					.chain(encoded_id)
 					.copied()
 					.collect::<Vec<u8>>()
 			})
 		}
 
-		fn save_kitty_to_indexing(kitty_id: T::KittyIndex) {
-			let key = Self::derived_key(frame_system::Module::<T>::block_number());
-			let data: IndexingData<T> = IndexingData(kitty_id);
+		fn save_kitty_to_indexing(kitty_id: T::KittyIndex, dna: [u8; 16]) {
+			let key = Self::derived_key(kitty_id);
+			let data = IndexingData(kitty_id, dna);
 			offchain_index::set(&key, &data.encode());
 		}
 
-		fn send_signed_tx(kitty_id: T::KittyIndex, payload: u32) -> Result<(), &'static str> {
-			let signer = Signer::<T, T::AuthorityId>::all_accounts();
-			if !signer.can_sign() {
-				return Err(
-					"No local accounts available. Consider adding one via `author_insertKey` RPC.",
+		pub fn is_asset_authority(who: &T::AccountId) -> bool {
+			Self::asset_authorities().contains(who)
+		}
+
+		// 写入kitty的资产评分
+		fn set_kitty_asset(kitty_id: T::KittyIndex, asset: u32) -> DispatchResult {
+			ensure!(Kitties::<T>::contains_key(kitty_id), Error::<T>::InvalidKittyId);
+			KittyAssets::<T>::insert(kitty_id, asset);
+			Ok(())
+		}
+
+		/// 根据DNA计算kitty的资产评分
+		///
+		/// 每个基因的高4位代表显性强度并计入评分，高低4位相同的基因比较稀有，额外加32分。
+		pub fn asset_score(dna: &[u8; 16]) -> u32 {
+			dna.iter()
+				.map(|gene| {
+					let (dominance, low) = (gene >> 4, gene & 0x0f);
+					u32::from(dominance) + if dominance == low { 32 } else { 0 }
+				})
+				.sum()
+		}
+
+		// 从链下索引读取kitty的DNA，节点需要开启 `--enable-offchain-indexing`
+		fn indexed_dna(kitty_id: T::KittyIndex) -> Option<[u8; 16]> {
+			StorageValueRef::persistent(&Self::derived_key(kitty_id))
+				.get::<IndexingData<T::KittyIndex>>()
+				.ok()
+				.flatten()
+				.map(|IndexingData(_, dna)| dna)
+		}
+
+		// 为还没有评分的kitty计算资产评分并提交回链上
+		//
+		// 交易进入交易池后仍可能被丢弃，所以游标只越过评分已经上链或已销毁的kitty，
+		// 其余的kitty每次运行都会重新提交，直到评分上链。
+		fn compute_kitty_assets() -> Result<(), &'static str> {
+			// 链下工作机可能并发运行，用锁避免重复提交
+			let mut lock =
+				StorageLock::<BlockAndTime<frame_system::Pallet<T>>>::with_block_and_time_deadline(
+					ASSET_LOCK_KEY,
+					LOCK_BLOCK_EXPIRATION,
+					Duration::from_millis(LOCK_TIMEOUT_EXPIRATION),
 				);
-			}
+			let _guard = lock.try_lock().map_err(|_| "Kitty asset computation is running")?;
 
-			let results = signer.send_signed_transaction(|_account| Call::update_kitty {
-				kitty_id,
-				asset: payload,
-			});
+			let is_done = |kitty_id: T::KittyIndex| {
+				KittyAssets::<T>::contains_key(kitty_id) || !Kitties::<T>::contains_key(kitty_id)
+			};
+			let cursor_ref = StorageValueRef::persistent(ASSET_CURSOR_KEY);
+			let mut cursor = cursor_ref.get::<T::KittyIndex>().ok().flatten().unwrap_or_default();
+			let next_kitty_id = Self::next_kitty_id();
+			while cursor < next_kitty_id && is_done(cursor) {
+				cursor = cursor + T::KittyIndex::from(1_u8);
+			}
+			cursor_ref.set(&cursor);
 
-			for (acc, res) in &results {
-				match res {
-					Ok(()) => log::info!("[{:?}] Submitted data:{:?}", acc.id, (kitty_id, payload)),
-					Err(e) => log::error!("[{:?}] Failed to submit transaction: {:?}", acc.id, e),
+			if !Signer::<T, T::AuthorityId>::any_account().can_sign() {
+				return Err(
+					"No local accounts available. Consider adding one via `author_insertKey` RPC.",
+				)
+			}
+			let mut kitty_id = cursor;
+			let mut submitted = 0;
+			while kitty_id < next_kitty_id && submitted < MAX_ASSETS_PER_RUN {
+				if !is_done(kitty_id) {
+					match Self::indexed_dna(kitty_id) {
+						Some(dna) => {
+							// 交易池中已有相同的评分交易时会提交失败，等待它上链即可
+							if let Err(e) = Self::send_asset_tx(kitty_id, Self::asset_score(&dna)) {
+								log::warn!("Kitty {:?} asset not submitted: {}", kitty_id, e);
+							}
+							submitted += 1;
+						},
+						// 创世kitty，以及节点未开启 `--enable-offchain-indexing` 时铸造的kitty
+						// 没有链下索引，跳过它们继续处理后面的kitty
+						None => log::debug!("Kitty {:?} missing from the offchain index", kitty_id),
+					}
 				}
+				kitty_id = kitty_id + T::KittyIndex::from(1_u8);
 			}
-
 			Ok(())
 		}
+
+		// 使用带签名payload的无签名交易提交评分：评分与账户无关，不需要支付手续费，
+		// 同时签名可以在交易池中验证数据来源
+		fn send_asset_tx(kitty_id: T::KittyIndex, asset: u32) -> Result<(), &'static str> {
+			let signer = Signer::<T, T::AuthorityId>::any_account();
+			match signer.send_unsigned_transaction(
+				|account| KittyAssetPayload { kitty_id, asset, public: account.public.clone() },
+				|payload, signature| Call::update_kitty_asset_unsigned_with_signed_payload {
+					payload,
+					signature,
+				},
+			) {
+				Some((_, Ok(()))) => {
+					log::info!("Submitted kitty asset: {:?}", (kitty_id, asset));
+					Ok(())
+				},
+				Some((_, Err(()))) => Err("Failed to submit kitty asset transaction"),
+				None => Err(
+					"No local accounts available. Consider adding one via `author_insertKey` RPC.",
+				),
+			}
+		}
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		// 托管交易必须在截止区块解除，不能等到有剩余权重时
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			Self::expire_trades(now)
+		}
+
+		// 利用区块的剩余权重清理过期报价
+		fn on_idle(now: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			Self::clean_expired_offers(now, remaining_weight)
+		}
+
 		fn offchain_worker(block_number: T::BlockNumber) {
-			let key = Self::derived_key(block_number);
-			let storage_ref = StorageValueRef::persistent(&key);
-
-			if let Ok(Some(data)) = storage_ref.get::<IndexingData<T>>() {
-				// Sleep 8000ms to simulate heavy calculation for kitty asset index.
-				let timeout = sp_io::offchain::timestamp()
-					.add(sp_runtime::offchain::Duration::from_millis(8000));
-				sp_io::offchain::sleep_until(timeout);
-
-				let kitty_id = data.0.into();
-
-				// if block_number % 2u32.into() != Zero::zero() {
-				// 	_ = Self::send_signed_tx(kitty_id, 1);
-				// } else {
-				// 	_ = Self::send_signed_tx(kitty_id, 2);
-				// }
+			if let Err(e) = Self::compute_kitty_assets() {
+				log::warn!("[{:?}] Kitty asset computation skipped: {}", block_number, e);
 			}
 		}
 	}
@@ -1,10 +1,14 @@
 use crate as pallet_kitties;
-use frame_support::parameter_types;
+use frame_support::{
+	parameter_types,
+	traits::{ConstU128, ConstU32},
+};
 use frame_system as system;
 use sp_core::H256;
 use sp_runtime::{
-	testing::Header,
-	traits::{BlakeTwo256, IdentityLookup},
+	testing::{Header, TestSignature, TestXt, UintAuthorityId},
+	traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentityLookup},
+	Permill,
 };
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
@@ -19,9 +23,12 @@ frame_support::construct_runtime!(
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		KittiesModule: pallet_kitties::{Pallet, Call, Storage, Event<T>},
+		KittiesModule: pallet_kitties::{
+			Pallet, Call, Storage, Config<T>, Event<T>, ValidateUnsigned,
+		},
 		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
 		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Storage},
+		Assets: pallet_assets::{Pallet, Call, Storage, Config<T>, Event<T>},
 	}
 );
 
@@ -76,9 +83,70 @@ impl pallet_balances::Config for Test {
 
 impl pallet_randomness_collective_flip::Config for Test {}
 
+impl pallet_assets::Config for Test {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = u32;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<u64>;
+	type AssetDeposit = ConstU128<1>;
+	type AssetAccountDeposit = ConstU128<10>;
+	type MetadataDepositBase = ConstU128<1>;
+	type MetadataDepositPerByte = ConstU128<1>;
+	type ApprovalDeposit = ConstU128<1>;
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = ();
+}
+
 parameter_types! {
 	pub const KittyStake: u128 = 1_000;
 	pub const MaxKittyIndex: u32 = 5;
+	pub const RevealDelay: u64 = 2;
+	pub const BreedingLockPeriod: u64 = 10;
+	pub const MaxNameLength: u32 = 16;
+	pub const MaxMetadataUriLength: u32 = 64;
+	pub const MetadataDepositPerByte: u128 = 10;
+	pub const RoyaltyRate: Permill = Permill::from_percent(10);
+}
+
+// 测试中使用 `UintAuthorityId` 作为链下工作机的签名密钥
+pub struct TestAuthId;
+
+impl frame_system::offchain::AppCrypto<UintAuthorityId, TestSignature> for TestAuthId {
+	type RuntimeAppPublic = UintAuthorityId;
+	type GenericSignature = TestSignature;
+	type GenericPublic = UintAuthorityId;
+}
+
+pub type Extrinsic = TestXt<Call, ()>;
+
+impl frame_system::offchain::SigningTypes for Test {
+	type Public = UintAuthorityId;
+	type Signature = TestSignature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+	Call: From<LocalCall>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+	Call: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: Call,
+		_public: UintAuthorityId,
+		_account: u64,
+		nonce: u64,
+	) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (nonce, ())))
+	}
 }
 
 impl pallet_kitties::Config for Test {
@@ -88,6 +156,19 @@ impl pallet_kitties::Config for Test {
 	type KittyStake = KittyStake;
 	type Currency = Balances;
 	type MaxKittyIndex = MaxKittyIndex;
+	type DnaMixer = ();
+	type RevealDelay = RevealDelay;
+	type BreedingLockPeriod = BreedingLockPeriod;
+	type MaxNameLength = MaxNameLength;
+	type MaxMetadataUriLength = MaxMetadataUriLength;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type AuthorityId = TestAuthId;
+	type AssetAuthorityOrigin = frame_system::EnsureRoot<u64>;
+	type MaxAssetAuthorities = ConstU32<2>;
+	type RoyaltyRate = RoyaltyRate;
+	type Assets = Assets;
+	type MaxTradesPerDeadline = ConstU32<2>;
+	type WeightInfo = ();
 }
 
 #[macro_export]
@@ -97,7 +178,15 @@ macro_rules! assert_has_event {
 	};
 }
 
+// 测试用的游戏内代币
+pub const GAME_TOKEN: u32 = 1;
+
 pub fn new_test_ext() -> sp_io::TestExternalities {
+	new_test_ext_with_kitties(vec![])
+}
+
+// 在创世时预先铸造指定的kitty
+pub fn new_test_ext_with_kitties(kitties: Vec<(u64, [u8; 16])>) -> sp_io::TestExternalities {
 	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
 
 	pallet_balances::GenesisConfig::<Test> {
@@ -106,6 +195,19 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
 	.assimilate_storage(&mut storage)
 	.unwrap();
 
+	pallet_assets::GenesisConfig::<Test> {
+		assets: vec![(GAME_TOKEN, 1, true, 1)],
+		metadata: vec![],
+		accounts: vec![(GAME_TOKEN, 2, 100_000), (GAME_TOKEN, 4, 100_000)],
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+
+	// 测试中链下工作机的签名密钥为 `UintAuthorityId(7)`
+	pallet_kitties::GenesisConfig::<Test> { kitties, asset_authorities: vec![7] }
+		.assimilate_storage(&mut storage)
+		.unwrap();
+
 	let mut ext = sp_io::TestExternalities::new(storage);
 	ext.execute_with(|| System::set_block_number(1));
 	ext
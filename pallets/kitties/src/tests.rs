@@ -1,6 +1,18 @@
 use super::*;
-use crate::mock::{new_test_ext, Event as TestEvent, KittiesModule, Origin, System, Test};
-use frame_support::{assert_noop, assert_ok};
+use crate::mock::{
+	new_test_ext, new_test_ext_with_kitties, Assets, Balances, Event as TestEvent, Extrinsic,
+	KittiesModule, Origin, System, Test, GAME_TOKEN,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	assert_noop, assert_ok,
+	unsigned::{TransactionSource, ValidateUnsigned},
+};
+use sp_runtime::{
+	testing::{TestSignature, UintAuthorityId},
+	transaction_validity::InvalidTransaction,
+	Permill,
+};
 
 /**************************Create_test*******************/
 #[test]
@@ -112,6 +124,23 @@ fn breed_failed_not_enough_balance() {
 	});
 }
 
+#[test]
+fn breed_failed_not_owner() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(2)));
+		// 不能用别人的kitty繁殖，也不能因此锁定别人的kitty
+		assert_noop!(KittiesModule::breed(Origin::signed(2), 0, 1), Error::<Test>::NotOwner);
+		assert_noop!(KittiesModule::breed(Origin::signed(3), 0, 1), Error::<Test>::NotOwner);
+		assert!(!BreedingLocks::<Test>::contains_key(0));
+
+		// 被授权后可以繁殖
+		assert_ok!(KittiesModule::approve(Origin::signed(1), 0, Some(2)));
+		assert_ok!(KittiesModule::breed(Origin::signed(2), 0, 1));
+		assert_eq!(KittyOwner::<Test>::get(2), Some(2));
+	});
+}
+
 #[test]
 fn breed_failed_same_parent() {
 	new_test_ext().execute_with(|| {
@@ -144,7 +173,7 @@ fn transfer_success() {
 }
 
 #[test]
-fn transfer_failed_buyer_not_enough_balance() {
+fn transfer_failed_recipient_not_exist() {
 	new_test_ext().execute_with(|| {
 		let account_id_1: u64 = 1;
 		let account_id_5: u64 = 5;
@@ -153,11 +182,24 @@ fn transfer_failed_buyer_not_enough_balance() {
 		assert_ok!(KittiesModule::create(Origin::signed(account_id_1)));
 		assert_noop!(
 			KittiesModule::transfer(Origin::signed(account_id_1), kitty_id, account_id_5),
-			Error::<Test>::NotEnoughBalance
+			Error::<Test>::RecipientNotExist
 		);
 	});
 }
 
+#[test]
+fn transfer_moves_sender_stake() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		let free_2 = Balances::free_balance(2);
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 0, 2));
+		// 接收人不需要出资，质押从转出人移到接收人名下
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 1_000);
+		assert_eq!(Balances::free_balance(2), free_2);
+	});
+}
+
 #[test]
 fn transfer_failed_not_owner() {
 	new_test_ext().execute_with(|| {
@@ -255,7 +297,7 @@ fn buy_success() {
 	new_test_ext().execute_with(|| {
 		let account_id_1: u64 = 1;
 		let account_id_2: u64 = 2;
-		let kitty_id = 1u32;
+		let kitty_id = 0u32;
 		let price = 1000u128;
 		// 创建Kitty
 		assert_ok!(KittiesModule::create(Origin::signed(account_id_1)));
@@ -264,12 +306,12 @@ fn buy_success() {
 
 		assert_ok!(KittiesModule::sell(Origin::signed(account_id_1), kitty_id, Some(price)));
 
-		assert_ok!(KittiesModule::sell(Origin::signed(account_id_1), kitty_id, Some(price)));
+		assert_ok!(KittiesModule::buy(Origin::signed(account_id_2), kitty_id));
 
 		// 检查拥有者
 		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(account_id_2));
-		// 检查创建成功事件
-		assert_has_event!(Event::<Test>::KittySold(account_id_1, account_id_2, kitty_id));
+		// 创作者出售自己的kitty，不收取版税
+		assert_has_event!(Event::<Test>::KittySold(account_id_1, account_id_2, kitty_id, price, 0));
 	});
 }
 
@@ -286,3 +328,1250 @@ fn buy_success() {
 // //         assert_has_event!(Event::<Test>::KittyCreated(account_id, kitty_id));
 // //     });
 // // }
+
+/**************************DnaMixer_test*******************/
+#[test]
+fn default_mixer_selects_bits_from_parents() {
+	let dna_1 = [0b1111_0000u8; 16];
+	let dna_2 = [0b0000_1111u8; 16];
+	let selector = [0b1010_1010u8; 16];
+	assert_eq!(<() as DnaMixer>::mix(&dna_1, &dna_2, &selector), [0b1010_0101u8; 16]);
+}
+
+#[test]
+fn dominant_mixer_inherits_dominant_genes() {
+	frame_support::parameter_types! {
+		pub const NoMutation: Permill = Permill::zero();
+	}
+	let dna_1 = [0xF0u8; 16];
+	let dna_2 = [0x1Fu8; 16];
+	let selector = [0u8; 16];
+	assert_eq!(DominantGeneMixer::<NoMutation>::mix(&dna_1, &dna_2, &selector), dna_1);
+	assert_eq!(DominantGeneMixer::<NoMutation>::mix(&dna_2, &dna_1, &selector), dna_1);
+}
+
+#[test]
+fn dominant_mixer_mutates_every_gene_at_full_chance() {
+	frame_support::parameter_types! {
+		pub const AlwaysMutate: Permill = Permill::one();
+	}
+	let dna = [0x55u8; 16];
+	let selector = [0u8; 16];
+	let mut expected = [0u8; 16];
+	// 每个基因都突变，翻转的位由突变随机数再次哈希的结果决定
+	let flip_seed = sp_io::hashing::blake2_256(&sp_io::hashing::blake2_256(&selector));
+	for i in 0..16 {
+		expected[i] = 0x55 ^ (1 << (flip_seed[i] & 0b111));
+	}
+	assert_eq!(DominantGeneMixer::<AlwaysMutate>::mix(&dna, &dna, &selector), expected);
+}
+
+#[test]
+fn dominant_mixer_mutates_genes_whose_draw_is_below_chance() {
+	frame_support::parameter_types! {
+		pub const HalfMutate: Permill = Permill::from_percent(50);
+	}
+	let dna = [0x55u8; 16];
+	let selector = [7u8; 16];
+	let mutation_seed = sp_io::hashing::blake2_256(&selector);
+	let flip_seed = sp_io::hashing::blake2_256(&mutation_seed);
+	let mut expected = dna;
+	// 50% 对应的阈值为 32768，随机数小于阈值的基因突变
+	for i in 0..16 {
+		let draw = u16::from_le_bytes([mutation_seed[2 * i], mutation_seed[2 * i + 1]]);
+		if draw < 32768 {
+			expected[i] ^= 1 << (flip_seed[i] & 0b111);
+		}
+	}
+	assert_eq!(DominantGeneMixer::<HalfMutate>::mix(&dna, &dna, &selector), expected);
+}
+
+/**************************CommitReveal_test*******************/
+fn commitment_of(account_id: u64, salt: sp_core::H256) -> sp_core::H256 {
+	use sp_runtime::traits::Hash;
+	<Test as frame_system::Config>::Hashing::hash_of(&(&account_id, &salt))
+}
+
+#[test]
+fn commit_reveal_create_success() {
+	new_test_ext().execute_with(|| {
+		let account_id: u64 = 1;
+		let kitty_id = 0u32;
+		let salt = sp_core::H256::repeat_byte(7);
+
+		assert_ok!(KittiesModule::commit_create(
+			Origin::signed(account_id),
+			commitment_of(account_id, salt)
+		));
+		assert_has_event!(Event::<Test>::DnaRequested(account_id, 3));
+		// 提交时已经质押
+		assert_eq!(Balances::reserved_balance(account_id), 1_000);
+
+		// 目标区块的哈希在下一个区块才可用
+		System::set_block_number(3);
+		assert_noop!(
+			KittiesModule::reveal(Origin::signed(account_id), salt),
+			Error::<Test>::RevealTooEarly
+		);
+
+		frame_system::BlockHash::<Test>::insert(3, sp_core::H256::repeat_byte(3));
+		System::set_block_number(4);
+		assert_ok!(KittiesModule::reveal(Origin::signed(account_id), salt));
+
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(account_id));
+		assert_eq!(DnaRequests::<Test>::get(account_id), None);
+		// 揭示时不再重复质押
+		assert_eq!(Balances::reserved_balance(account_id), 1_000);
+		assert_has_event!(Event::<Test>::KittyCreatedAndBreed(account_id, kitty_id));
+	});
+}
+
+#[test]
+fn commit_failed_request_pending() {
+	new_test_ext().execute_with(|| {
+		let account_id: u64 = 1;
+		let commitment = commitment_of(account_id, sp_core::H256::repeat_byte(7));
+		assert_ok!(KittiesModule::commit_create(Origin::signed(account_id), commitment));
+		assert_noop!(
+			KittiesModule::commit_create(Origin::signed(account_id), commitment),
+			Error::<Test>::RequestAlreadyPending
+		);
+	});
+}
+
+#[test]
+fn reveal_failed_wrong_salt() {
+	new_test_ext().execute_with(|| {
+		let account_id: u64 = 1;
+		let commitment = commitment_of(account_id, sp_core::H256::repeat_byte(7));
+		assert_ok!(KittiesModule::commit_create(Origin::signed(account_id), commitment));
+		System::set_block_number(4);
+		assert_noop!(
+			KittiesModule::reveal(Origin::signed(account_id), sp_core::H256::repeat_byte(8)),
+			Error::<Test>::InvalidReveal
+		);
+	});
+}
+
+#[test]
+fn commit_reveal_breed_success() {
+	new_test_ext().execute_with(|| {
+		let account_id: u64 = 1;
+		let salt = sp_core::H256::repeat_byte(7);
+		assert_ok!(KittiesModule::create(Origin::signed(account_id)));
+		assert_ok!(KittiesModule::create(Origin::signed(account_id)));
+		assert_ok!(KittiesModule::commit_breed(
+			Origin::signed(account_id),
+			0,
+			1,
+			commitment_of(account_id, salt)
+		));
+
+		frame_system::BlockHash::<Test>::insert(3, sp_core::H256::repeat_byte(3));
+		System::set_block_number(4);
+		assert_ok!(KittiesModule::reveal(Origin::signed(account_id), salt));
+		assert_eq!(KittyOwner::<Test>::get(2), Some(account_id));
+	});
+}
+
+#[test]
+fn commit_breed_locks_parents_until_reveal() {
+	new_test_ext().execute_with(|| {
+		let account_id: u64 = 1;
+		let salt = sp_core::H256::repeat_byte(7);
+		assert_ok!(KittiesModule::create(Origin::signed(account_id)));
+		assert_ok!(KittiesModule::create(Origin::signed(account_id)));
+		assert_ok!(KittiesModule::commit_breed(
+			Origin::signed(account_id),
+			0,
+			1,
+			commitment_of(account_id, salt)
+		));
+
+		// 揭示前父母不能销毁、转移或碎片化，避免看到结果后改变父母
+		assert_noop!(
+			KittiesModule::burn(Origin::signed(account_id), 0),
+			Error::<Test>::KittyBreedingPending
+		);
+		assert_noop!(
+			KittiesModule::transfer(Origin::signed(account_id), 1, 2),
+			Error::<Test>::KittyBreedingPending
+		);
+		assert_noop!(
+			KittiesModule::fractionalize(Origin::signed(account_id), 0, 10, 100),
+			Error::<Test>::KittyBreedingPending
+		);
+
+		frame_system::BlockHash::<Test>::insert(3, sp_core::H256::repeat_byte(3));
+		System::set_block_number(4);
+		assert_ok!(KittiesModule::reveal(Origin::signed(account_id), salt));
+		assert!(!PendingBreedingParents::<Test>::contains_key(0));
+		assert!(!PendingBreedingParents::<Test>::contains_key(1));
+		assert_ok!(KittiesModule::transfer(Origin::signed(account_id), 1, 2));
+
+		// 放弃请求同样解除锁定
+		assert_ok!(KittiesModule::commit_breed(
+			Origin::signed(account_id),
+			0,
+			2,
+			commitment_of(account_id, salt)
+		));
+		assert_ok!(KittiesModule::cancel_dna_request(Origin::signed(account_id)));
+		assert!(!PendingBreedingParents::<Test>::contains_key(0));
+		assert!(!PendingBreedingParents::<Test>::contains_key(2));
+	});
+}
+
+#[test]
+fn commit_breed_failed_not_owner() {
+	new_test_ext().execute_with(|| {
+		let salt = sp_core::H256::repeat_byte(7);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(2)));
+		assert_noop!(
+			KittiesModule::commit_breed(Origin::signed(2), 0, 1, commitment_of(2, salt)),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			KittiesModule::commit_breed(Origin::signed(2), 0, 2, commitment_of(2, salt)),
+			Error::<Test>::NotOwner
+		);
+		// 别人的kitty没有被锁定
+		assert!(!PendingBreedingParents::<Test>::contains_key(0));
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 0, 4));
+
+		// 拥有者授权所有kitty后可以繁殖
+		assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(1), 2, true));
+		assert_ok!(KittiesModule::commit_breed(Origin::signed(2), 1, 2, commitment_of(2, salt)));
+		assert_eq!(PendingBreedingParents::<Test>::get(1), 1);
+	});
+}
+
+#[test]
+fn reveal_failed_when_target_hash_pruned() {
+	new_test_ext().execute_with(|| {
+		let account_id: u64 = 1;
+		let salt = sp_core::H256::repeat_byte(7);
+		assert_ok!(KittiesModule::commit_create(
+			Origin::signed(account_id),
+			commitment_of(account_id, salt)
+		));
+
+		// 目标区块的哈希已被清理，不能退回到其他随机源
+		System::set_block_number(300);
+		assert_noop!(
+			KittiesModule::reveal(Origin::signed(account_id), salt),
+			Error::<Test>::RequestExpired
+		);
+
+		// 过期的请求只能放弃，质押被没收
+		assert_ok!(KittiesModule::cancel_dna_request(Origin::signed(account_id)));
+		assert_has_event!(Event::<Test>::DnaRequestForfeited(account_id, 1_000));
+		assert_eq!(DnaRequests::<Test>::get(account_id), None);
+		assert_eq!(Balances::reserved_balance(account_id), 0);
+		assert_eq!(Balances::free_balance(account_id), 10_000_000_000 - 1_000);
+		assert_ok!(KittiesModule::commit_create(
+			Origin::signed(account_id),
+			commitment_of(account_id, salt)
+		));
+	});
+}
+
+#[test]
+fn cancel_dna_request_refunds_before_target_block() {
+	new_test_ext().execute_with(|| {
+		let account_id: u64 = 1;
+		let commitment = commitment_of(account_id, sp_core::H256::repeat_byte(7));
+		assert_noop!(
+			KittiesModule::cancel_dna_request(Origin::signed(account_id)),
+			Error::<Test>::NoPendingRequest
+		);
+		assert_ok!(KittiesModule::commit_create(Origin::signed(account_id), commitment));
+
+		System::set_block_number(3);
+		assert_ok!(KittiesModule::cancel_dna_request(Origin::signed(account_id)));
+		assert_has_event!(Event::<Test>::DnaRequestCancelled(account_id));
+		assert_eq!(DnaRequests::<Test>::get(account_id), None);
+		assert_eq!(Balances::reserved_balance(account_id), 0);
+		assert_eq!(Balances::free_balance(account_id), 10_000_000_000);
+	});
+}
+
+/**************************Burn_test*******************/
+#[test]
+fn burn_success() {
+	new_test_ext().execute_with(|| {
+		let account_id: u64 = 1;
+		let kitty_id = 0u32;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id)));
+		assert_ok!(KittiesModule::sell(Origin::signed(account_id), kitty_id, Some(100)));
+		assert_eq!(Balances::reserved_balance(account_id), 1_000);
+
+		assert_ok!(KittiesModule::burn(Origin::signed(account_id), kitty_id));
+		// 检查kitty及索引、上架信息已删除，质押已解除
+		assert_eq!(Kitties::<Test>::get(kitty_id), None);
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), None);
+		assert_eq!(OwnedKitties::<Test>::get(account_id, kitty_id), None);
+		assert_eq!(KittiesShop::<Test>::get(kitty_id), None);
+		assert_eq!(KittyCreators::<Test>::get(kitty_id), None);
+		assert_eq!(Balances::reserved_balance(account_id), 0);
+		assert_has_event!(Event::<Test>::KittyBurned(account_id, kitty_id));
+	});
+}
+
+#[test]
+fn burn_failed_not_owner() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_noop!(KittiesModule::burn(Origin::signed(2), 0), Error::<Test>::NotOwner);
+	});
+}
+
+#[test]
+fn burn_failed_breeding_locked() {
+	new_test_ext().execute_with(|| {
+		let account_id: u64 = 1;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id)));
+		assert_ok!(KittiesModule::create(Origin::signed(account_id)));
+		assert_ok!(KittiesModule::breed(Origin::signed(account_id), 0, 1));
+		assert_noop!(
+			KittiesModule::burn(Origin::signed(account_id), 0),
+			Error::<Test>::KittyBreedingLocked
+		);
+		// 子kitty没有锁定，可以销毁
+		assert_ok!(KittiesModule::burn(Origin::signed(account_id), 2));
+
+		// 锁定期过后父母可以销毁
+		System::set_block_number(11);
+		assert_ok!(KittiesModule::burn(Origin::signed(account_id), 0));
+	});
+}
+
+#[test]
+fn owned_kitties_follow_transfer() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 0, 2));
+		assert_eq!(OwnedKitties::<Test>::get(1, 0), None);
+		assert_eq!(OwnedKitties::<Test>::get(2, 0), Some(()));
+	});
+}
+
+/**************************Metadata_test*******************/
+#[test]
+fn set_name_and_uri_success() {
+	new_test_ext().execute_with(|| {
+		let account_id: u64 = 1;
+		let kitty_id = 0u32;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id)));
+
+		assert_ok!(KittiesModule::set_name(Origin::signed(account_id), kitty_id, b"tom".to_vec()));
+		assert_has_event!(Event::<Test>::KittyNamed(account_id, kitty_id, b"tom".to_vec()));
+		// 质押 + 3 字节押金
+		assert_eq!(Balances::reserved_balance(account_id), 1_000 + 30);
+
+		let uri = b"ipfs://tom".to_vec();
+		assert_ok!(KittiesModule::set_metadata_uri(
+			Origin::signed(account_id),
+			kitty_id,
+			Some(uri.clone())
+		));
+		assert_has_event!(Event::<Test>::KittyMetadataUriSet(
+			account_id,
+			kitty_id,
+			Some(uri.clone())
+		));
+		assert_eq!(Balances::reserved_balance(account_id), 1_000 + 130);
+
+		let info = KittiesModule::kitty_info(kitty_id).unwrap();
+		assert_eq!(info.owner, account_id);
+		assert_eq!(info.name, b"tom".to_vec());
+		assert_eq!(info.metadata_uri, Some(uri));
+
+		// 清除URI后退还对应押金
+		assert_ok!(KittiesModule::set_metadata_uri(Origin::signed(account_id), kitty_id, None));
+		assert_eq!(Balances::reserved_balance(account_id), 1_000 + 30);
+	});
+}
+
+#[test]
+fn set_name_failed_not_owner_or_too_long() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_noop!(
+			KittiesModule::set_name(Origin::signed(2), 0, b"tom".to_vec()),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			KittiesModule::set_name(Origin::signed(1), 0, vec![b'a'; 17]),
+			Error::<Test>::NameTooLong
+		);
+	});
+}
+
+#[test]
+fn burn_clears_metadata_and_deposit() {
+	new_test_ext().execute_with(|| {
+		let account_id: u64 = 1;
+		assert_ok!(KittiesModule::create(Origin::signed(account_id)));
+		assert_ok!(KittiesModule::set_name(Origin::signed(account_id), 0, b"tom".to_vec()));
+		assert_ok!(KittiesModule::burn(Origin::signed(account_id), 0));
+		assert_eq!(KittyNames::<Test>::get(0), None);
+		assert_eq!(MetadataDeposits::<Test>::get(0), None);
+		assert_eq!(Balances::reserved_balance(account_id), 0);
+	});
+}
+
+/**************************Approval_test*******************/
+#[test]
+fn transfer_from_by_approved_account() {
+	new_test_ext().execute_with(|| {
+		let owner: u64 = 1;
+		let delegate: u64 = 2;
+		let kitty_id = 0u32;
+		assert_ok!(KittiesModule::create(Origin::signed(owner)));
+		assert_noop!(
+			KittiesModule::transfer_from(Origin::signed(delegate), kitty_id, delegate),
+			Error::<Test>::NotApproved
+		);
+
+		assert_ok!(KittiesModule::approve(Origin::signed(owner), kitty_id, Some(delegate)));
+		assert_has_event!(Event::<Test>::KittyApproved(owner, Some(delegate), kitty_id));
+		assert_ok!(KittiesModule::transfer_from(Origin::signed(delegate), kitty_id, delegate));
+
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(delegate));
+		// 所有权变更后授权被清除
+		assert_eq!(KittyApprovals::<Test>::get(kitty_id), None);
+		assert_has_event!(Event::<Test>::KittyTransferred(owner, delegate, kitty_id));
+	});
+}
+
+#[test]
+fn transfer_from_by_operator() {
+	new_test_ext().execute_with(|| {
+		let owner: u64 = 1;
+		let operator: u64 = 2;
+		assert_ok!(KittiesModule::create(Origin::signed(owner)));
+		assert_ok!(KittiesModule::create(Origin::signed(owner)));
+		assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(owner), operator, true));
+		assert_has_event!(Event::<Test>::ApprovalForAll(owner, operator, true));
+
+		assert_ok!(KittiesModule::transfer_from(Origin::signed(operator), 0, operator));
+		assert_ok!(KittiesModule::transfer_from(Origin::signed(operator), 1, operator));
+
+		assert_ok!(KittiesModule::create(Origin::signed(owner)));
+		assert_ok!(KittiesModule::set_approval_for_all(Origin::signed(owner), operator, false));
+		assert_noop!(
+			KittiesModule::transfer_from(Origin::signed(operator), 2, operator),
+			Error::<Test>::NotApproved
+		);
+	});
+}
+
+#[test]
+fn transfer_clears_approval() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::approve(Origin::signed(1), 0, Some(4)));
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 0, 2));
+		assert_noop!(
+			KittiesModule::transfer_from(Origin::signed(4), 0, 4),
+			Error::<Test>::NotApproved
+		);
+	});
+}
+
+/**************************Gift_test*******************/
+#[test]
+fn gift_claim_success() {
+	new_test_ext().execute_with(|| {
+		let sender: u64 = 1;
+		let recipient: u64 = 2;
+		let kitty_id = 0u32;
+		assert_ok!(KittiesModule::create(Origin::signed(sender)));
+		assert_ok!(KittiesModule::gift(Origin::signed(sender), kitty_id, recipient));
+		assert_has_event!(Event::<Test>::KittyGiftOffered(sender, recipient, kitty_id));
+		// 领取前所有权和质押都不变
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(sender));
+		assert_eq!(Balances::reserved_balance(recipient), 0);
+
+		assert_noop!(
+			KittiesModule::claim_gift(Origin::signed(4), kitty_id),
+			Error::<Test>::NotGiftRecipient
+		);
+		assert_ok!(KittiesModule::claim_gift(Origin::signed(recipient), kitty_id));
+		assert_eq!(KittyOwner::<Test>::get(kitty_id), Some(recipient));
+		assert_eq!(Balances::reserved_balance(sender), 0);
+		assert_eq!(Balances::reserved_balance(recipient), 1_000);
+		assert_eq!(PendingGifts::<Test>::get(kitty_id), None);
+		assert_has_event!(Event::<Test>::KittyGiftClaimed(sender, recipient, kitty_id));
+	});
+}
+
+#[test]
+fn gift_cancel_success() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::gift(Origin::signed(1), 0, 2));
+		assert_ok!(KittiesModule::cancel_gift(Origin::signed(1), 0));
+		assert_noop!(KittiesModule::claim_gift(Origin::signed(2), 0), Error::<Test>::NoPendingGift);
+	});
+}
+
+#[test]
+fn gift_claim_failed_not_enough_balance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::gift(Origin::signed(1), 0, 3));
+		assert_noop!(
+			KittiesModule::claim_gift(Origin::signed(3), 0),
+			Error::<Test>::NotEnoughBalance
+		);
+	});
+}
+
+/**************************Query_test*******************/
+#[test]
+fn ancestors_and_descendants_follow_breeding() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 2, 3));
+
+		// 祖先按代返回，深度受参数限制
+		assert_eq!(KittiesModule::ancestors(4, 1), vec![2, 3]);
+		assert_eq!(KittiesModule::ancestors(4, 5), vec![2, 3, 0, 1]);
+		assert!(KittiesModule::ancestors(0, 5).is_empty());
+
+		let mut descendants = KittiesModule::descendants(0, 0);
+		descendants.sort();
+		assert_eq!(descendants, vec![2, 4]);
+		// 超出范围的页为空
+		assert!(KittiesModule::descendants(0, 1).is_empty());
+	});
+}
+
+#[test]
+fn kitties_for_sale_only_lists_priced_kitties() {
+	new_test_ext().execute_with(|| {
+		for _ in 0..3 {
+			assert_ok!(KittiesModule::create(Origin::signed(1)));
+		}
+		assert_ok!(KittiesModule::sell(Origin::signed(1), 0, Some(100)));
+		assert_ok!(KittiesModule::sell_for_asset(Origin::signed(1), 1, GAME_TOKEN, 200));
+		assert_ok!(KittiesModule::sell(Origin::signed(1), 2, None));
+
+		let mut for_sale = KittiesModule::kitties_for_sale(0);
+		for_sale.sort();
+		assert_eq!(for_sale, vec![(0, 100, None), (1, 200, Some(GAME_TOKEN))]);
+		assert!(KittiesModule::kitties_for_sale(1).is_empty());
+		assert_eq!(KittiesModule::price_of(0), Some((100, None)));
+		assert_eq!(KittiesModule::price_of(1), Some((200, Some(GAME_TOKEN))));
+		assert_eq!(KittiesModule::price_of(2), None);
+	});
+}
+
+/**************************Genesis_test*******************/
+#[test]
+fn genesis_kitties_are_minted_with_stake() {
+	new_test_ext_with_kitties(vec![(1, [1; 16]), (1, [2; 16]), (2, [3; 16])]).execute_with(|| {
+		assert_eq!(KittiesModule::next_kitty_id(), 3);
+		assert_eq!(KittyOwner::<Test>::get(0), Some(1));
+		assert_eq!(KittyOwner::<Test>::get(2), Some(2));
+		assert_eq!(KittiesModule::kitties_info(1).map(|kitty| kitty.dna), Some([2; 16]));
+		assert!(OwnedKitties::<Test>::contains_key(1, 1));
+		assert!(OwnedKitties::<Test>::contains_key(2, 2));
+		// 每只kitty都为所有者质押
+		assert_eq!(Balances::reserved_balance(1), 2_000);
+		assert_eq!(Balances::reserved_balance(2), 1_000);
+
+		// 新创建的kitty从创世kitty之后开始编号
+		assert_ok!(KittiesModule::create(Origin::signed(2)));
+		assert_eq!(KittyOwner::<Test>::get(3), Some(2));
+	});
+}
+
+/**************************Asset_test*******************/
+// 取出交易池中唯一的评分交易
+fn pop_asset_call(
+	transactions: &mut Vec<Vec<u8>>,
+) -> (KittyAssetPayload<UintAuthorityId, u32>, TestSignature) {
+	let tx = transactions.pop().unwrap();
+	assert!(transactions.is_empty());
+	let tx = Extrinsic::decode(&mut &*tx).unwrap();
+	assert_eq!(tx.signature, None);
+	match tx.call {
+		crate::mock::Call::KittiesModule(
+			Call::update_kitty_asset_unsigned_with_signed_payload { payload, signature },
+		) => (payload, signature),
+		call => panic!("Unexpected call: {:?}", call),
+	}
+}
+
+#[test]
+fn offchain_worker_submits_asset_score() {
+	use frame_support::traits::OffchainWorker;
+	use sp_core::offchain::{
+		testing::{TestOffchainExt, TestTransactionPoolExt},
+		OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+	};
+
+	let mut ext = new_test_ext();
+	let (offchain, _state) = TestOffchainExt::with_offchain_db(ext.offchain_db());
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	UintAuthorityId::set_all_keys(vec![7]);
+
+	ext.execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		// 链下索引还没有写入，链下工作机不读取链上的DNA
+		KittiesModule::offchain_worker(1);
+		assert!(pool_state.read().transactions.is_empty());
+	});
+	// 区块导入后链下索引才写入
+	ext.persist_offchain_overlay();
+
+	ext.execute_with(|| {
+		let asset = KittiesModule::asset_score(&KittiesModule::kitties_info(0).unwrap().dna);
+		KittiesModule::offchain_worker(1);
+		let (payload, _) = pop_asset_call(&mut pool_state.write().transactions);
+		assert_eq!(payload, KittyAssetPayload { kitty_id: 0, asset, public: UintAuthorityId(7) });
+
+		// 交易被丢弃、评分没有上链时重新提交
+		KittiesModule::offchain_worker(2);
+		let (payload, signature) = pop_asset_call(&mut pool_state.write().transactions);
+		assert_ok!(KittiesModule::update_kitty_asset_unsigned_with_signed_payload(
+			Origin::none(),
+			payload,
+			signature
+		));
+		assert_eq!(KittiesModule::kitty_assets(0), Some(asset));
+		assert_has_event!(Event::<Test>::KittyAssetUpdated(None, 0, asset));
+
+		// 评分已经上链的kitty不会重复提交
+		KittiesModule::offchain_worker(3);
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn offchain_worker_skips_kitties_missing_from_index() {
+	use frame_support::traits::OffchainWorker;
+	use sp_core::offchain::{
+		testing::{TestOffchainExt, TestTransactionPoolExt},
+		OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+	};
+
+	// 创世kitty在创世构建时铸造，没有写入链下索引
+	let mut ext = new_test_ext_with_kitties(vec![(1, [1; 16]), (2, [2; 16])]);
+	let (offchain, _state) = TestOffchainExt::with_offchain_db(ext.offchain_db());
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	UintAuthorityId::set_all_keys(vec![7]);
+
+	ext.execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+	});
+	ext.persist_offchain_overlay();
+
+	ext.execute_with(|| {
+		let asset = KittiesModule::asset_score(&KittiesModule::kitties_info(2).unwrap().dna);
+		KittiesModule::offchain_worker(1);
+		let (payload, _) = pop_asset_call(&mut pool_state.write().transactions);
+		assert_eq!(payload, KittyAssetPayload { kitty_id: 2, asset, public: UintAuthorityId(7) });
+
+		// 没有索引的kitty不会阻塞之后的运行
+		KittiesModule::offchain_worker(2);
+		let (payload, _) = pop_asset_call(&mut pool_state.write().transactions);
+		assert_eq!(payload.kitty_id, 2);
+	});
+}
+
+#[test]
+fn unsigned_asset_with_bad_signature_is_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		let payload = KittyAssetPayload { kitty_id: 0, asset: 10, public: UintAuthorityId(7) };
+		let call = Call::update_kitty_asset_unsigned_with_signed_payload {
+			payload,
+			signature: TestSignature(8, vec![]),
+		};
+		assert_eq!(
+			KittiesModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadProof.into()
+		);
+	});
+}
+
+#[test]
+fn asset_from_non_authority_is_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+
+		// 签名有效，但密钥不在资产评分提交者集合中
+		let payload = KittyAssetPayload { kitty_id: 0, asset: 10, public: UintAuthorityId(8) };
+		let signature = TestSignature(8, payload.encode());
+		let call = Call::update_kitty_asset_unsigned_with_signed_payload {
+			payload: payload.clone(),
+			signature: signature.clone(),
+		};
+		assert_eq!(
+			KittiesModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadSigner.into()
+		);
+		assert_noop!(
+			KittiesModule::update_kitty_asset_unsigned_with_signed_payload(
+				Origin::none(),
+				payload,
+				signature
+			),
+			Error::<Test>::NotAssetAuthority
+		);
+		assert_noop!(
+			KittiesModule::update_kitty_asset(Origin::signed(8), 0, 10),
+			Error::<Test>::NotAssetAuthority
+		);
+	});
+}
+
+#[test]
+fn update_kitty_asset_signed_success() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KittiesModule::update_kitty_asset(Origin::signed(7), 0, 10),
+			Error::<Test>::InvalidKittyId
+		);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::update_kitty_asset(Origin::signed(7), 0, 10));
+		assert_has_event!(Event::<Test>::KittyAssetUpdated(Some(7), 0, 10));
+		assert_eq!(KittiesModule::kitty_info(0).unwrap().asset, Some(10));
+
+		// 销毁kitty时清除资产评分
+		assert_ok!(KittiesModule::burn(Origin::signed(1), 0));
+		assert_eq!(KittiesModule::kitty_assets(0), None);
+	});
+}
+
+#[test]
+fn root_manages_asset_authorities() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KittiesModule::add_asset_authority(Origin::signed(7), 8),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			KittiesModule::add_asset_authority(Origin::root(), 7),
+			Error::<Test>::AlreadyAssetAuthority
+		);
+
+		assert_ok!(KittiesModule::add_asset_authority(Origin::root(), 8));
+		assert_has_event!(Event::<Test>::AssetAuthorityAdded(8));
+		assert!(KittiesModule::is_asset_authority(&8));
+
+		assert_ok!(KittiesModule::remove_asset_authority(Origin::root(), 7));
+		assert_has_event!(Event::<Test>::AssetAuthorityRemoved(7));
+		assert_eq!(KittiesModule::asset_authorities().into_inner(), vec![8]);
+		assert_noop!(
+			KittiesModule::remove_asset_authority(Origin::root(), 7),
+			Error::<Test>::NotAssetAuthority
+		);
+	});
+}
+
+/**************************Offer_test*******************/
+#[test]
+fn offer_accept_success() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		let seller_balance = Balances::free_balance(1);
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), 0, 5_000, 10));
+		assert_has_event!(Event::<Test>::OfferMade(2, 0, 5_000, 10));
+		assert_eq!(Balances::reserved_balance(2), 5_000);
+
+		assert_ok!(KittiesModule::accept_offer(Origin::signed(1), 0, 2));
+		assert_has_event!(Event::<Test>::OfferAccepted(1, 2, 0, 5_000));
+		assert_eq!(KittyOwner::<Test>::get(0), Some(2));
+		assert_eq!(KittiesModule::offers(0, 2), None);
+		// 报价金额支付给卖家，质押随kitty转给买家
+		assert_eq!(Balances::free_balance(1), seller_balance + 5_000 + 1_000);
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 1_000);
+	});
+}
+
+#[test]
+fn offer_withdraw_success() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), 0, 5_000, 10));
+		assert_ok!(KittiesModule::withdraw_offer(Origin::signed(2), 0));
+		assert_has_event!(Event::<Test>::OfferWithdrawn(2, 0));
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_noop!(KittiesModule::accept_offer(Origin::signed(1), 0, 2), Error::<Test>::NoOffer);
+	});
+}
+
+#[test]
+fn make_offer_failed() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			KittiesModule::make_offer(Origin::signed(2), 0, 5_000, 10),
+			Error::<Test>::InvalidKittyId
+		);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_noop!(
+			KittiesModule::make_offer(Origin::signed(1), 0, 5_000, 10),
+			Error::<Test>::NoBuySelf
+		);
+		assert_noop!(
+			KittiesModule::make_offer(Origin::signed(2), 0, 5_000, 1),
+			Error::<Test>::InvalidOfferExpiry
+		);
+		assert_noop!(
+			KittiesModule::make_offer(Origin::signed(3), 0, 5_000, 10),
+			Error::<Test>::NotEnoughBalance
+		);
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), 0, 5_000, 10));
+		assert_noop!(
+			KittiesModule::make_offer(Origin::signed(2), 0, 6_000, 10),
+			Error::<Test>::OfferAlreadyExists
+		);
+	});
+}
+
+#[test]
+fn expired_offers_are_cleaned_on_idle() {
+	use frame_support::traits::OnIdle;
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::make_offer(Origin::signed(2), 0, 5_000, 3));
+		assert_ok!(KittiesModule::make_offer(Origin::signed(4), 0, 5_000, 10));
+
+		System::set_block_number(3);
+		assert_noop!(
+			KittiesModule::accept_offer(Origin::signed(1), 0, 2),
+			Error::<Test>::OfferExpired
+		);
+
+		KittiesModule::on_idle(3, u64::MAX);
+		assert_has_event!(Event::<Test>::OfferExpired(2, 0));
+		assert_eq!(KittiesModule::offers(0, 2), None);
+		assert_eq!(Balances::reserved_balance(2), 0);
+		// 未过期的报价不受影响
+		assert!(KittiesModule::offers(0, 4).is_some());
+		assert_eq!(Balances::reserved_balance(4), 5_000);
+	});
+}
+
+/**************************Royalty_test*******************/
+#[test]
+fn buy_pays_royalty_to_creator() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_eq!(KittiesModule::kitty_creators(0), Some(1));
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 0, 2));
+
+		// 二次销售：2 卖给 4，创作者 1 获得 10% 版税
+		let creator_balance = Balances::free_balance(1);
+		let seller_balance = Balances::free_balance(2);
+		assert_ok!(KittiesModule::sell(Origin::signed(2), 0, Some(10_000)));
+		assert_ok!(KittiesModule::buy(Origin::signed(4), 0));
+
+		assert_eq!(KittyOwner::<Test>::get(0), Some(4));
+		assert_has_event!(Event::<Test>::RoyaltyPaid(1, 0, 1_000));
+		assert_has_event!(Event::<Test>::KittySold(2, 4, 0, 9_000, 1_000));
+		assert_eq!(Balances::free_balance(1), creator_balance + 1_000);
+		assert_eq!(Balances::free_balance(2), seller_balance + 9_000 + 1_000);
+		assert_eq!(Balances::free_balance(4), 90_000 - 10_000 - 1_000);
+	});
+}
+
+#[test]
+fn accept_offer_pays_royalty_to_breeder() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::breed(Origin::signed(1), 0, 1));
+		// 繁殖者即为子kitty的创作者
+		assert_eq!(KittiesModule::kitty_creators(2), Some(1));
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 2, 2));
+
+		let creator_balance = Balances::free_balance(1);
+		assert_ok!(KittiesModule::make_offer(Origin::signed(4), 2, 20_000, 10));
+		assert_ok!(KittiesModule::accept_offer(Origin::signed(2), 2, 4));
+
+		assert_has_event!(Event::<Test>::RoyaltyPaid(1, 2, 2_000));
+		assert_has_event!(Event::<Test>::KittySold(2, 4, 2, 18_000, 2_000));
+		assert_eq!(Balances::free_balance(1), creator_balance + 2_000);
+		assert_eq!(Balances::reserved_balance(4), 1_000);
+	});
+}
+
+#[test]
+fn royalty_below_existential_deposit_goes_to_seller() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(4)));
+		assert_ok!(KittiesModule::transfer(Origin::signed(4), 0, 2));
+		// 创作者账户被清空，版税低于最低余额无法支付
+		assert_ok!(Balances::transfer(Origin::signed(4), 1, 89_000));
+		assert!(!System::account_exists(&4));
+
+		let seller_balance = Balances::free_balance(2);
+		assert_ok!(KittiesModule::sell(Origin::signed(2), 0, Some(1_000)));
+		assert_ok!(KittiesModule::buy(Origin::signed(1), 0));
+
+		assert_has_event!(Event::<Test>::KittySold(2, 1, 0, 1_000, 0));
+		assert_eq!(Balances::free_balance(2), seller_balance + 1_000 + 1_000);
+	});
+}
+
+/**************************Asset_sale_test*******************/
+#[test]
+fn buy_with_game_token_success() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 0, 2));
+		assert_ok!(KittiesModule::sell_for_asset(Origin::signed(2), 0, GAME_TOKEN, 10_000));
+		assert_has_event!(Event::<Test>::KittyInSellForAsset(2, 0, GAME_TOKEN, 10_000));
+		assert_eq!(KittiesModule::kitty_info(0).unwrap().price_asset, Some(GAME_TOKEN));
+
+		assert_ok!(KittiesModule::buy(Origin::signed(4), 0));
+		assert_eq!(KittyOwner::<Test>::get(0), Some(4));
+		assert_eq!(KittiesModule::listing_assets(0), None);
+		assert_has_event!(Event::<Test>::KittySold(2, 4, 0, 9_000, 1_000));
+		// 售价和版税用游戏内代币支付，原生代币只用于质押
+		assert_eq!(Assets::balance(GAME_TOKEN, 4), 90_000);
+		assert_eq!(Assets::balance(GAME_TOKEN, 2), 109_000);
+		assert_eq!(Assets::balance(GAME_TOKEN, 1), 1_000);
+		assert_eq!(Balances::free_balance(4), 89_000);
+		assert_eq!(Balances::reserved_balance(4), 1_000);
+	});
+}
+
+#[test]
+fn buy_with_game_token_failed_not_enough_balance() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::sell_for_asset(Origin::signed(1), 0, GAME_TOKEN, 200_000));
+		assert_noop!(KittiesModule::buy(Origin::signed(2), 0), Error::<Test>::NotEnoughBalance);
+		assert_noop!(
+			KittiesModule::sell_for_asset(Origin::signed(2), 0, GAME_TOKEN, 100),
+			Error::<Test>::NotOwner
+		);
+	});
+}
+
+#[test]
+fn sell_in_native_currency_clears_listing_asset() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::sell_for_asset(Origin::signed(1), 0, GAME_TOKEN, 1_000));
+		assert_ok!(KittiesModule::sell(Origin::signed(1), 0, Some(1_000)));
+		assert_eq!(KittiesModule::listing_assets(0), None);
+
+		let seller_balance = Balances::free_balance(1);
+		assert_ok!(KittiesModule::buy(Origin::signed(2), 0));
+		assert_eq!(Balances::free_balance(1), seller_balance + 1_000 + 1_000);
+		assert_eq!(Assets::balance(GAME_TOKEN, 2), 100_000);
+	});
+}
+
+#[test]
+fn sell_for_asset_failed_unknown_asset() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_noop!(
+			KittiesModule::sell_for_asset(Origin::signed(1), 0, GAME_TOKEN + 1, 1_000),
+			Error::<Test>::UnknownAsset
+		);
+		assert_eq!(KittiesModule::price_of(0), None);
+	});
+}
+
+/**************************Trade_test*******************/
+#[test]
+fn kitty_swap_trade_success() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(2)));
+		assert_ok!(KittiesModule::open_trade(
+			Origin::signed(1),
+			0,
+			2,
+			TradeConsideration::Kitty(1),
+			10
+		));
+		assert_has_event!(Event::<Test>::TradeOpened(0, 1, 2, 0, TradeConsideration::Kitty(1), 10));
+		// 托管中的kitty不能转移或出售
+		assert_eq!(KittiesModule::escrowed_kitties(0), Some(0));
+		assert_noop!(
+			KittiesModule::transfer(Origin::signed(1), 0, 4),
+			Error::<Test>::KittyInEscrow
+		);
+		assert_noop!(
+			KittiesModule::sell(Origin::signed(1), 0, Some(100)),
+			Error::<Test>::KittyInEscrow
+		);
+		// 乙方托管对价之前不能确认
+		assert_noop!(
+			KittiesModule::confirm_trade(Origin::signed(1), 0),
+			Error::<Test>::TradeNotLocked
+		);
+
+		assert_ok!(KittiesModule::lock_trade(Origin::signed(2), 0));
+		assert_eq!(KittiesModule::escrowed_kitties(1), Some(0));
+		assert_ok!(KittiesModule::confirm_trade(Origin::signed(1), 0));
+		assert_noop!(
+			KittiesModule::confirm_trade(Origin::signed(1), 0),
+			Error::<Test>::TradeAlreadyConfirmed
+		);
+		assert_ok!(KittiesModule::confirm_trade(Origin::signed(2), 0));
+
+		assert_has_event!(Event::<Test>::TradeExecuted(0));
+		assert_eq!(KittyOwner::<Test>::get(0), Some(2));
+		assert_eq!(KittyOwner::<Test>::get(1), Some(1));
+		assert_eq!(KittiesModule::trades(0), None);
+		assert_eq!(KittiesModule::escrowed_kitties(0), None);
+		assert_eq!(KittiesModule::escrowed_kitties(1), None);
+		assert_eq!(Balances::reserved_balance(1), 1_000);
+		assert_eq!(Balances::reserved_balance(2), 1_000);
+	});
+}
+
+#[test]
+fn funds_trade_pays_seller_and_royalty() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 0, 2));
+		assert_ok!(KittiesModule::open_trade(
+			Origin::signed(2),
+			0,
+			4,
+			TradeConsideration::Funds(10_000),
+			10
+		));
+		assert_ok!(KittiesModule::lock_trade(Origin::signed(4), 0));
+		assert_eq!(Balances::reserved_balance(4), 10_000);
+
+		let seller_balance = Balances::free_balance(2);
+		assert_ok!(KittiesModule::confirm_trade(Origin::signed(4), 0));
+		assert_ok!(KittiesModule::confirm_trade(Origin::signed(2), 0));
+
+		assert_eq!(KittyOwner::<Test>::get(0), Some(4));
+		assert_has_event!(Event::<Test>::RoyaltyPaid(1, 0, 1_000));
+		assert_has_event!(Event::<Test>::KittySold(2, 4, 0, 9_000, 1_000));
+		assert_eq!(Balances::free_balance(2), seller_balance + 9_000);
+		// 乙方质押的资金已支付，kitty的质押随kitty转给乙方
+		assert_eq!(Balances::reserved_balance(4), 1_000);
+		assert_eq!(Balances::reserved_balance(2), 0);
+	});
+}
+
+#[test]
+fn cancel_trade_unwinds_escrow() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::open_trade(
+			Origin::signed(1),
+			0,
+			4,
+			TradeConsideration::Funds(10_000),
+			10
+		));
+		assert_ok!(KittiesModule::lock_trade(Origin::signed(4), 0));
+		assert_ok!(KittiesModule::confirm_trade(Origin::signed(1), 0));
+		assert_noop!(
+			KittiesModule::cancel_trade(Origin::signed(2), 0),
+			Error::<Test>::NotTradeParty
+		);
+
+		assert_ok!(KittiesModule::cancel_trade(Origin::signed(4), 0));
+		assert_has_event!(Event::<Test>::TradeCancelled(0, 4));
+		assert_eq!(KittiesModule::trades(0), None);
+		assert_eq!(KittiesModule::escrowed_kitties(0), None);
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert_noop!(KittiesModule::lock_trade(Origin::signed(4), 0), Error::<Test>::TradeNotFound);
+		assert_ok!(KittiesModule::transfer(Origin::signed(1), 0, 2));
+	});
+}
+
+#[test]
+fn unfinished_trade_is_unwound_at_deadline() {
+	use frame_support::traits::OnInitialize;
+
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::open_trade(
+			Origin::signed(1),
+			0,
+			4,
+			TradeConsideration::Funds(10_000),
+			3
+		));
+		assert_ok!(KittiesModule::lock_trade(Origin::signed(4), 0));
+
+		System::set_block_number(3);
+		KittiesModule::on_initialize(3);
+		assert_has_event!(Event::<Test>::TradeExpired(0));
+		assert_eq!(KittiesModule::trades(0), None);
+		assert_eq!(KittiesModule::escrowed_kitties(0), None);
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert_eq!(KittyOwner::<Test>::get(0), Some(1));
+	});
+}
+
+#[test]
+fn open_trade_failed() {
+	new_test_ext().execute_with(|| {
+		let funds = TradeConsideration::Funds(100);
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_noop!(
+			KittiesModule::open_trade(Origin::signed(2), 0, 4, funds.clone(), 10),
+			Error::<Test>::NotOwner
+		);
+		assert_noop!(
+			KittiesModule::open_trade(Origin::signed(1), 0, 1, funds.clone(), 10),
+			Error::<Test>::NoBuySelf
+		);
+		assert_noop!(
+			KittiesModule::open_trade(Origin::signed(1), 0, 4, funds.clone(), 1),
+			Error::<Test>::InvalidTradeDeadline
+		);
+		assert_ok!(KittiesModule::open_trade(Origin::signed(1), 0, 4, funds.clone(), 10));
+		assert_noop!(
+			KittiesModule::open_trade(Origin::signed(1), 0, 4, funds.clone(), 10),
+			Error::<Test>::KittyInEscrow
+		);
+		// 同一截止区块最多两个交易
+		assert_ok!(KittiesModule::open_trade(Origin::signed(1), 1, 4, funds.clone(), 10));
+		assert_noop!(
+			KittiesModule::open_trade(Origin::signed(1), 2, 4, funds, 10),
+			Error::<Test>::TooManyTrades
+		);
+	});
+}
+
+/**************************Fraction_test*******************/
+#[test]
+fn fractionalized_kitty_cannot_be_transferred_sold_or_bred() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::sell(Origin::signed(1), 0, Some(100)));
+		assert_ok!(KittiesModule::fractionalize(Origin::signed(1), 0, 100, 50_000));
+		assert_has_event!(Event::<Test>::KittyFractionalized(1, 0, 100, 50_000));
+		assert_eq!(KittiesModule::kitty_shares(0, 1), 100);
+		assert_eq!(KittiesShop::<Test>::get(0), None);
+
+		assert_noop!(
+			KittiesModule::transfer(Origin::signed(1), 0, 2),
+			Error::<Test>::KittyFractionalized
+		);
+		assert_noop!(
+			KittiesModule::sell(Origin::signed(1), 0, Some(100)),
+			Error::<Test>::KittyFractionalized
+		);
+		assert_noop!(
+			KittiesModule::breed(Origin::signed(1), 0, 1),
+			Error::<Test>::KittyFractionalized
+		);
+		assert_noop!(
+			KittiesModule::fractionalize(Origin::signed(1), 0, 10, 50_000),
+			Error::<Test>::KittyFractionalized
+		);
+		assert_noop!(
+			KittiesModule::fractionalize(Origin::signed(1), 1, 0, 50_000),
+			Error::<Test>::InvalidShareCount
+		);
+	});
+}
+
+#[test]
+fn fractionalize_failed_zero_reserve_price() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_noop!(
+			KittiesModule::fractionalize(Origin::signed(1), 0, 100, 0),
+			Error::<Test>::InvalidReservePrice
+		);
+		assert_eq!(KittiesModule::kitty_shares(0, 1), 0);
+	});
+}
+
+#[test]
+fn redeem_with_all_shares_success() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::fractionalize(Origin::signed(1), 0, 100, 50_000));
+		assert_ok!(KittiesModule::transfer_shares(Origin::signed(1), 0, 2, 60));
+		assert_ok!(KittiesModule::transfer_shares(Origin::signed(1), 0, 4, 40));
+		assert_has_event!(Event::<Test>::SharesTransferred(1, 4, 0, 40));
+		assert_eq!(KittiesModule::kitty_shares(0, 1), 0);
+		assert_noop!(
+			KittiesModule::transfer_shares(Origin::signed(1), 0, 2, 1),
+			Error::<Test>::NotEnoughShares
+		);
+		assert_noop!(KittiesModule::redeem(Origin::signed(2), 0), Error::<Test>::NotEnoughShares);
+
+		assert_ok!(KittiesModule::transfer_shares(Origin::signed(4), 0, 2, 40));
+		assert_ok!(KittiesModule::redeem(Origin::signed(2), 0));
+		assert_has_event!(Event::<Test>::KittyRedeemed(2, 0));
+		assert_eq!(KittyOwner::<Test>::get(0), Some(2));
+		assert_eq!(KittiesModule::fractions(0), None);
+		assert_eq!(KittiesModule::kitty_shares(0, 2), 0);
+		// 质押随kitty转给赎回人
+		assert_eq!(Balances::reserved_balance(1), 0);
+		assert_eq!(Balances::reserved_balance(2), 1_000);
+		assert_ok!(KittiesModule::transfer(Origin::signed(2), 0, 4));
+	});
+}
+
+#[test]
+fn buyout_pays_shareholders_pro_rata() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(KittiesModule::create(Origin::signed(1)));
+		assert_ok!(KittiesModule::fractionalize(Origin::signed(1), 0, 4, 10_000));
+		assert_ok!(KittiesModule::transfer_shares(Origin::signed(1), 0, 2, 1));
+		assert_ok!(KittiesModule::transfer_shares(Origin::signed(1), 0, 4, 1));
+
+		assert_ok!(KittiesModule::buyout(Origin::signed(2), 0));
+		assert_has_event!(Event::<Test>::KittyBoughtOut(2, 0, 10_000));
+		assert_eq!(KittyOwner::<Test>::get(0), Some(2));
+		assert_eq!(KittiesModule::fractions(0), None);
+		assert_eq!(Balances::reserved_balance(2), 10_000 + 1_000);
+		// 买断款领取完之前不能再次碎片化
+		assert_noop!(
+			KittiesModule::fractionalize(Origin::signed(2), 0, 4, 10_000),
+			Error::<Test>::BuyoutPending
+		);
+
+		let balance_1 = Balances::free_balance(1);
+		let balance_4 = Balances::free_balance(4);
+		assert_ok!(KittiesModule::claim_buyout_proceeds(Origin::signed(1), 0));
+		assert_has_event!(Event::<Test>::BuyoutProceedsClaimed(1, 0, 2, 5_000));
+		assert_ok!(KittiesModule::claim_buyout_proceeds(Origin::signed(4), 0));
+		assert_noop!(
+			KittiesModule::claim_buyout_proceeds(Origin::signed(4), 0),
+			Error::<Test>::NotEnoughShares
+		);
+		assert_ok!(KittiesModule::claim_buyout_proceeds(Origin::signed(2), 0));
+		assert_eq!(Balances::free_balance(1), balance_1 + 5_000);
+		assert_eq!(Balances::free_balance(4), balance_4 + 2_500);
+		assert_eq!(Balances::reserved_balance(2), 1_000);
+		assert_eq!(KittiesModule::buyouts(0), None);
+		assert_noop!(
+			KittiesModule::claim_buyout_proceeds(Origin::signed(1), 0),
+			Error::<Test>::NoBuyout
+		);
+	});
+}
@@ -0,0 +1,847 @@
+// This file is part of Substrate.
+
+// Copyright (C) 2022 Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Placeholder weights for pallet_kitties
+//!
+//! THESE WEIGHTS ARE NOT GENERATED. The values and the storage annotations below are
+//! hand-written estimates from reading the call implementations; they have not been
+//! measured on any hardware. Regenerate this file from `benchmarking.rs` before relying
+//! on it for fees:
+//!
+//! ./target/release/node-template benchmark pallet --chain dev --execution=wasm
+//!     --wasm-execution=compiled --pallet pallet_kitties --extrinsic '*' --steps 20
+//!     --repeat 10 --output=./pallets/kitties/src/weights.rs
+//!     --template .maintain/frame-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_kitties.
+pub trait WeightInfo {
+    fn create() -> Weight;
+    fn breed() -> Weight;
+    fn transfer() -> Weight;
+    fn sell() -> Weight;
+    fn sell_for_asset() -> Weight;
+    fn buy() -> Weight;
+    fn make_offer() -> Weight;
+    fn withdraw_offer() -> Weight;
+    fn accept_offer() -> Weight;
+    fn open_trade() -> Weight;
+    fn lock_trade() -> Weight;
+    fn confirm_trade() -> Weight;
+    fn cancel_trade() -> Weight;
+    fn fractionalize() -> Weight;
+    fn transfer_shares() -> Weight;
+    fn redeem() -> Weight;
+    fn buyout() -> Weight;
+    fn claim_buyout_proceeds() -> Weight;
+    fn commit_create() -> Weight;
+    fn commit_breed() -> Weight;
+    fn reveal() -> Weight;
+    fn cancel_dna_request() -> Weight;
+    fn approve() -> Weight;
+    fn set_approval_for_all() -> Weight;
+    fn transfer_from() -> Weight;
+    fn gift() -> Weight;
+    fn cancel_gift() -> Weight;
+    fn claim_gift() -> Weight;
+    fn burn() -> Weight;
+    fn set_name() -> Weight;
+    fn set_metadata_uri() -> Weight;
+    fn update_kitty_asset() -> Weight;
+    fn update_kitty_asset_unsigned_with_signed_payload() -> Weight;
+    fn add_asset_authority() -> Weight;
+    fn remove_asset_authority() -> Weight;
+}
+
+/// Placeholder weights for pallet_kitties, to be replaced by benchmark output.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+    // Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+    // Storage: System Account (r:1 w:1)
+    // Storage: KittiesModule NextKittyId (r:1 w:1)
+    // Storage: KittiesModule Kitties (r:0 w:1)
+    // Storage: KittiesModule KittyOwner (r:0 w:1)
+    // Storage: KittiesModule OwnedKitties (r:0 w:1)
+    // Storage: KittiesModule KittyCreators (r:0 w:1)
+    fn create() -> Weight {
+        (39_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
+    }
+    // Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+    // Storage: KittiesModule Kitties (r:2 w:1)
+    // Storage: KittiesModule NextKittyId (r:1 w:1)
+    // Storage: System Account (r:1 w:1)
+    // Storage: KittiesModule KittyOwner (r:0 w:1)
+    // Storage: KittiesModule OwnedKitties (r:0 w:1)
+    // Storage: KittiesModule KittyParents (r:0 w:1)
+    // Storage: KittiesModule KittyChildren (r:0 w:2)
+    // Storage: KittiesModule KittyCreators (r:0 w:1)
+    // Storage: KittiesModule BreedingLocks (r:0 w:2)
+    fn breed() -> Weight {
+        (53_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(11 as Weight))
+    }
+    // Storage: KittiesModule Kitties (r:1 w:0)
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule OwnedKitties (r:0 w:2)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn transfer() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule KittiesShop (r:1 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    fn sell() -> Weight {
+        (21_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    fn sell_for_asset() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: KittiesModule KittiesShop (r:1 w:1)
+    // Storage: KittiesModule ListingAssets (r:1 w:1)
+    // Storage: KittiesModule KittyCreators (r:1 w:0)
+    // Storage: System Account (r:3 w:3)
+    // Storage: KittiesModule OwnedKitties (r:0 w:2)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn buy() -> Weight {
+        (73_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule Offers (r:1 w:1)
+    // Storage: System Account (r:1 w:1)
+    // Storage: KittiesModule NextOfferExpiryCheck (r:1 w:1)
+    // Storage: KittiesModule OfferExpiries (r:0 w:1)
+    fn make_offer() -> Weight {
+        (33_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    // Storage: KittiesModule Offers (r:1 w:1)
+    // Storage: System Account (r:1 w:1)
+    // Storage: KittiesModule OfferExpiries (r:0 w:1)
+    fn withdraw_offer() -> Weight {
+        (27_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: KittiesModule Offers (r:1 w:1)
+    // Storage: KittiesModule KittyCreators (r:1 w:0)
+    // Storage: System Account (r:3 w:3)
+    // Storage: KittiesModule OfferExpiries (r:0 w:1)
+    // Storage: KittiesModule OwnedKitties (r:0 w:2)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn accept_offer() -> Weight {
+        (74_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(12 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:1)
+    // Storage: KittiesModule NextTradeId (r:1 w:1)
+    // Storage: KittiesModule TradeDeadlines (r:1 w:1)
+    // Storage: KittiesModule Trades (r:0 w:1)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn open_trade() -> Weight {
+        (36_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(7 as Weight))
+    }
+    // Storage: KittiesModule Trades (r:1 w:1)
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:1)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn lock_trade() -> Weight {
+        (31_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    // Storage: KittiesModule Trades (r:1 w:1)
+    // Storage: KittiesModule TradeDeadlines (r:1 w:1)
+    // Storage: KittiesModule EscrowedKitties (r:2 w:2)
+    // Storage: KittiesModule KittyOwner (r:0 w:2)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule OwnedKitties (r:0 w:4)
+    // Storage: KittiesModule KittiesShop (r:0 w:2)
+    // Storage: KittiesModule ListingAssets (r:0 w:2)
+    // Storage: KittiesModule KittyApprovals (r:0 w:2)
+    // Storage: KittiesModule PendingGifts (r:0 w:2)
+    fn confirm_trade() -> Weight {
+        (84_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(22 as Weight))
+    }
+    // Storage: KittiesModule Trades (r:1 w:1)
+    // Storage: KittiesModule TradeDeadlines (r:1 w:1)
+    // Storage: System Account (r:1 w:1)
+    // Storage: KittiesModule EscrowedKitties (r:0 w:1)
+    fn cancel_trade() -> Weight {
+        (32_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(3 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:0)
+    // Storage: KittiesModule Fractions (r:1 w:1)
+    // Storage: KittiesModule Buyouts (r:1 w:0)
+    // Storage: KittiesModule KittyShares (r:0 w:1)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn fractionalize() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
+    }
+    // Storage: KittiesModule KittyShares (r:2 w:2)
+    fn transfer_shares() -> Weight {
+        (22_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    // Storage: KittiesModule Fractions (r:1 w:1)
+    // Storage: KittiesModule KittyShares (r:1 w:1)
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:0)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule OwnedKitties (r:0 w:2)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn redeem() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(6 as Weight))
+            .saturating_add(T::DbWeight::get().writes(11 as Weight))
+    }
+    // Storage: KittiesModule Fractions (r:1 w:1)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:0)
+    // Storage: KittiesModule Buyouts (r:0 w:1)
+    // Storage: KittiesModule OwnedKitties (r:0 w:2)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn buyout() -> Weight {
+        (62_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(11 as Weight))
+    }
+    // Storage: KittiesModule Buyouts (r:1 w:1)
+    // Storage: KittiesModule KittyShares (r:1 w:1)
+    // Storage: System Account (r:2 w:2)
+    fn claim_buyout_proceeds() -> Weight {
+        (41_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    // Storage: KittiesModule DnaRequests (r:1 w:1)
+    // Storage: System Account (r:1 w:1)
+    fn commit_create() -> Weight {
+        (28_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(2 as Weight))
+    }
+    // Storage: KittiesModule Kitties (r:2 w:0)
+    // Storage: KittiesModule KittyOwner (r:2 w:0)
+    // Storage: KittiesModule EscrowedKitties (r:2 w:0)
+    // Storage: KittiesModule PendingBreedingParents (r:2 w:2)
+    // Storage: KittiesModule Fractions (r:2 w:0)
+    // Storage: KittiesModule DnaRequests (r:1 w:1)
+    // Storage: System Account (r:1 w:1)
+    fn commit_breed() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(12 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    // Storage: KittiesModule DnaRequests (r:1 w:1)
+    // Storage: System BlockHash (r:1 w:0)
+    // Storage: KittiesModule PendingBreedingParents (r:2 w:2)
+    // Storage: KittiesModule Kitties (r:2 w:1)
+    // Storage: KittiesModule Fractions (r:2 w:0)
+    // Storage: KittiesModule NextKittyId (r:1 w:1)
+    // Storage: KittiesModule KittyOwner (r:0 w:1)
+    // Storage: KittiesModule OwnedKitties (r:0 w:1)
+    // Storage: KittiesModule KittyCreators (r:0 w:1)
+    // Storage: KittiesModule KittyParents (r:0 w:1)
+    // Storage: KittiesModule KittyChildren (r:0 w:2)
+    // Storage: KittiesModule BreedingLocks (r:0 w:2)
+    fn reveal() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(14 as Weight))
+    }
+    // Storage: KittiesModule DnaRequests (r:1 w:1)
+    // Storage: KittiesModule PendingBreedingParents (r:2 w:2)
+    // Storage: System Account (r:1 w:1)
+    fn cancel_dna_request() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule OperatorApprovals (r:1 w:0)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    fn approve() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Storage: KittiesModule OperatorApprovals (r:0 w:1)
+    fn set_approval_for_all() -> Weight {
+        (15_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: KittiesModule KittyApprovals (r:1 w:1)
+    // Storage: KittiesModule OperatorApprovals (r:1 w:0)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:0)
+    // Storage: KittiesModule PendingBreedingParents (r:1 w:0)
+    // Storage: KittiesModule Fractions (r:1 w:0)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule OwnedKitties (r:0 w:2)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn transfer_from() -> Weight {
+        (48_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:0)
+    // Storage: KittiesModule PendingBreedingParents (r:1 w:0)
+    // Storage: KittiesModule Fractions (r:1 w:0)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn gift() -> Weight {
+        (22_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(4 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule PendingGifts (r:1 w:1)
+    fn cancel_gift() -> Weight {
+        (19_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Storage: KittiesModule PendingGifts (r:1 w:1)
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:0)
+    // Storage: KittiesModule PendingBreedingParents (r:1 w:0)
+    // Storage: KittiesModule Fractions (r:1 w:0)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule OwnedKitties (r:0 w:2)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    fn claim_gift() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(9 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:0)
+    // Storage: KittiesModule PendingBreedingParents (r:1 w:0)
+    // Storage: KittiesModule Fractions (r:1 w:0)
+    // Storage: KittiesModule BreedingLocks (r:1 w:1)
+    // Storage: KittiesModule MetadataDeposits (r:1 w:1)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule Kitties (r:0 w:1)
+    // Storage: KittiesModule OwnedKitties (r:0 w:1)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    // Storage: KittiesModule KittyNames (r:0 w:1)
+    // Storage: KittiesModule KittyMetadataUris (r:0 w:1)
+    // Storage: KittiesModule KittyAssets (r:0 w:1)
+    // Storage: KittiesModule KittyCreators (r:0 w:1)
+    fn burn() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(16 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule KittyMetadataUris (r:1 w:0)
+    // Storage: KittiesModule MetadataDeposits (r:1 w:1)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule KittyNames (r:0 w:1)
+    fn set_name() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule KittyNames (r:1 w:0)
+    // Storage: KittiesModule MetadataDeposits (r:1 w:1)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule KittyMetadataUris (r:0 w:1)
+    fn set_metadata_uri() -> Weight {
+        (36_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(5 as Weight))
+            .saturating_add(T::DbWeight::get().writes(4 as Weight))
+    }
+    // Storage: KittiesModule AssetAuthorities (r:1 w:0)
+    // Storage: KittiesModule Kitties (r:1 w:0)
+    // Storage: KittiesModule KittyAssets (r:0 w:1)
+    fn update_kitty_asset() -> Weight {
+        (22_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Storage: KittiesModule AssetAuthorities (r:1 w:0)
+    // Storage: KittiesModule Kitties (r:1 w:0)
+    // Storage: KittiesModule KittyAssets (r:0 w:1)
+    fn update_kitty_asset_unsigned_with_signed_payload() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(2 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Storage: KittiesModule AssetAuthorities (r:1 w:1)
+    fn add_asset_authority() -> Weight {
+        (18_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+    // Storage: KittiesModule AssetAuthorities (r:1 w:1)
+    fn remove_asset_authority() -> Weight {
+        (18_000_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(1 as Weight))
+            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+    }
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+    // Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+    // Storage: System Account (r:1 w:1)
+    // Storage: KittiesModule NextKittyId (r:1 w:1)
+    // Storage: KittiesModule Kitties (r:0 w:1)
+    // Storage: KittiesModule KittyOwner (r:0 w:1)
+    // Storage: KittiesModule OwnedKitties (r:0 w:1)
+    // Storage: KittiesModule KittyCreators (r:0 w:1)
+    fn create() -> Weight {
+        (39_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(6 as Weight))
+    }
+    // Storage: RandomnessCollectiveFlip RandomMaterial (r:1 w:0)
+    // Storage: KittiesModule Kitties (r:2 w:1)
+    // Storage: KittiesModule NextKittyId (r:1 w:1)
+    // Storage: System Account (r:1 w:1)
+    // Storage: KittiesModule KittyOwner (r:0 w:1)
+    // Storage: KittiesModule OwnedKitties (r:0 w:1)
+    // Storage: KittiesModule KittyParents (r:0 w:1)
+    // Storage: KittiesModule KittyChildren (r:0 w:2)
+    // Storage: KittiesModule KittyCreators (r:0 w:1)
+    // Storage: KittiesModule BreedingLocks (r:0 w:2)
+    fn breed() -> Weight {
+        (53_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(11 as Weight))
+    }
+    // Storage: KittiesModule Kitties (r:1 w:0)
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule OwnedKitties (r:0 w:2)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn transfer() -> Weight {
+        (45_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule KittiesShop (r:1 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    fn sell() -> Weight {
+        (21_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    fn sell_for_asset() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: KittiesModule KittiesShop (r:1 w:1)
+    // Storage: KittiesModule ListingAssets (r:1 w:1)
+    // Storage: KittiesModule KittyCreators (r:1 w:0)
+    // Storage: System Account (r:3 w:3)
+    // Storage: KittiesModule OwnedKitties (r:0 w:2)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn buy() -> Weight {
+        (73_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(10 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule Offers (r:1 w:1)
+    // Storage: System Account (r:1 w:1)
+    // Storage: KittiesModule NextOfferExpiryCheck (r:1 w:1)
+    // Storage: KittiesModule OfferExpiries (r:0 w:1)
+    fn make_offer() -> Weight {
+        (33_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    // Storage: KittiesModule Offers (r:1 w:1)
+    // Storage: System Account (r:1 w:1)
+    // Storage: KittiesModule OfferExpiries (r:0 w:1)
+    fn withdraw_offer() -> Weight {
+        (27_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(3 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: KittiesModule Offers (r:1 w:1)
+    // Storage: KittiesModule KittyCreators (r:1 w:0)
+    // Storage: System Account (r:3 w:3)
+    // Storage: KittiesModule OfferExpiries (r:0 w:1)
+    // Storage: KittiesModule OwnedKitties (r:0 w:2)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn accept_offer() -> Weight {
+        (74_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(12 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:1)
+    // Storage: KittiesModule NextTradeId (r:1 w:1)
+    // Storage: KittiesModule TradeDeadlines (r:1 w:1)
+    // Storage: KittiesModule Trades (r:0 w:1)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn open_trade() -> Weight {
+        (36_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(7 as Weight))
+    }
+    // Storage: KittiesModule Trades (r:1 w:1)
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:1)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn lock_trade() -> Weight {
+        (31_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    // Storage: KittiesModule Trades (r:1 w:1)
+    // Storage: KittiesModule TradeDeadlines (r:1 w:1)
+    // Storage: KittiesModule EscrowedKitties (r:2 w:2)
+    // Storage: KittiesModule KittyOwner (r:0 w:2)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule OwnedKitties (r:0 w:4)
+    // Storage: KittiesModule KittiesShop (r:0 w:2)
+    // Storage: KittiesModule ListingAssets (r:0 w:2)
+    // Storage: KittiesModule KittyApprovals (r:0 w:2)
+    // Storage: KittiesModule PendingGifts (r:0 w:2)
+    fn confirm_trade() -> Weight {
+        (84_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(22 as Weight))
+    }
+    // Storage: KittiesModule Trades (r:1 w:1)
+    // Storage: KittiesModule TradeDeadlines (r:1 w:1)
+    // Storage: System Account (r:1 w:1)
+    // Storage: KittiesModule EscrowedKitties (r:0 w:1)
+    fn cancel_trade() -> Weight {
+        (32_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(3 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:0)
+    // Storage: KittiesModule Fractions (r:1 w:1)
+    // Storage: KittiesModule Buyouts (r:1 w:0)
+    // Storage: KittiesModule KittyShares (r:0 w:1)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn fractionalize() -> Weight {
+        (30_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(5 as Weight))
+    }
+    // Storage: KittiesModule KittyShares (r:2 w:2)
+    fn transfer_shares() -> Weight {
+        (22_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    // Storage: KittiesModule Fractions (r:1 w:1)
+    // Storage: KittiesModule KittyShares (r:1 w:1)
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:0)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule OwnedKitties (r:0 w:2)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn redeem() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(6 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(11 as Weight))
+    }
+    // Storage: KittiesModule Fractions (r:1 w:1)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:0)
+    // Storage: KittiesModule Buyouts (r:0 w:1)
+    // Storage: KittiesModule OwnedKitties (r:0 w:2)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn buyout() -> Weight {
+        (62_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(11 as Weight))
+    }
+    // Storage: KittiesModule Buyouts (r:1 w:1)
+    // Storage: KittiesModule KittyShares (r:1 w:1)
+    // Storage: System Account (r:2 w:2)
+    fn claim_buyout_proceeds() -> Weight {
+        (41_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    // Storage: KittiesModule DnaRequests (r:1 w:1)
+    // Storage: System Account (r:1 w:1)
+    fn commit_create() -> Weight {
+        (28_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(2 as Weight))
+    }
+    // Storage: KittiesModule Kitties (r:2 w:0)
+    // Storage: KittiesModule KittyOwner (r:2 w:0)
+    // Storage: KittiesModule EscrowedKitties (r:2 w:0)
+    // Storage: KittiesModule PendingBreedingParents (r:2 w:2)
+    // Storage: KittiesModule Fractions (r:2 w:0)
+    // Storage: KittiesModule DnaRequests (r:1 w:1)
+    // Storage: System Account (r:1 w:1)
+    fn commit_breed() -> Weight {
+        (40_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(12 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    // Storage: KittiesModule DnaRequests (r:1 w:1)
+    // Storage: System BlockHash (r:1 w:0)
+    // Storage: KittiesModule PendingBreedingParents (r:2 w:2)
+    // Storage: KittiesModule Kitties (r:2 w:1)
+    // Storage: KittiesModule Fractions (r:2 w:0)
+    // Storage: KittiesModule NextKittyId (r:1 w:1)
+    // Storage: KittiesModule KittyOwner (r:0 w:1)
+    // Storage: KittiesModule OwnedKitties (r:0 w:1)
+    // Storage: KittiesModule KittyCreators (r:0 w:1)
+    // Storage: KittiesModule KittyParents (r:0 w:1)
+    // Storage: KittiesModule KittyChildren (r:0 w:2)
+    // Storage: KittiesModule BreedingLocks (r:0 w:2)
+    fn reveal() -> Weight {
+        (55_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(9 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(14 as Weight))
+    }
+    // Storage: KittiesModule DnaRequests (r:1 w:1)
+    // Storage: KittiesModule PendingBreedingParents (r:2 w:2)
+    // Storage: System Account (r:1 w:1)
+    fn cancel_dna_request() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule OperatorApprovals (r:1 w:0)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    fn approve() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Storage: KittiesModule OperatorApprovals (r:0 w:1)
+    fn set_approval_for_all() -> Weight {
+        (15_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: KittiesModule KittyApprovals (r:1 w:1)
+    // Storage: KittiesModule OperatorApprovals (r:1 w:0)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:0)
+    // Storage: KittiesModule PendingBreedingParents (r:1 w:0)
+    // Storage: KittiesModule Fractions (r:1 w:0)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule OwnedKitties (r:0 w:2)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn transfer_from() -> Weight {
+        (48_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:0)
+    // Storage: KittiesModule PendingBreedingParents (r:1 w:0)
+    // Storage: KittiesModule Fractions (r:1 w:0)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    fn gift() -> Weight {
+        (22_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(4 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule PendingGifts (r:1 w:1)
+    fn cancel_gift() -> Weight {
+        (19_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Storage: KittiesModule PendingGifts (r:1 w:1)
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:0)
+    // Storage: KittiesModule PendingBreedingParents (r:1 w:0)
+    // Storage: KittiesModule Fractions (r:1 w:0)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule OwnedKitties (r:0 w:2)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    fn claim_gift() -> Weight {
+        (50_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(7 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(9 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:1)
+    // Storage: KittiesModule EscrowedKitties (r:1 w:0)
+    // Storage: KittiesModule PendingBreedingParents (r:1 w:0)
+    // Storage: KittiesModule Fractions (r:1 w:0)
+    // Storage: KittiesModule BreedingLocks (r:1 w:1)
+    // Storage: KittiesModule MetadataDeposits (r:1 w:1)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule Kitties (r:0 w:1)
+    // Storage: KittiesModule OwnedKitties (r:0 w:1)
+    // Storage: KittiesModule KittiesShop (r:0 w:1)
+    // Storage: KittiesModule ListingAssets (r:0 w:1)
+    // Storage: KittiesModule KittyApprovals (r:0 w:1)
+    // Storage: KittiesModule PendingGifts (r:0 w:1)
+    // Storage: KittiesModule KittyNames (r:0 w:1)
+    // Storage: KittiesModule KittyMetadataUris (r:0 w:1)
+    // Storage: KittiesModule KittyAssets (r:0 w:1)
+    // Storage: KittiesModule KittyCreators (r:0 w:1)
+    fn burn() -> Weight {
+        (60_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(8 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(16 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule KittyMetadataUris (r:1 w:0)
+    // Storage: KittiesModule MetadataDeposits (r:1 w:1)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule KittyNames (r:0 w:1)
+    fn set_name() -> Weight {
+        (35_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    // Storage: KittiesModule KittyOwner (r:1 w:0)
+    // Storage: KittiesModule KittyNames (r:1 w:0)
+    // Storage: KittiesModule MetadataDeposits (r:1 w:1)
+    // Storage: System Account (r:2 w:2)
+    // Storage: KittiesModule KittyMetadataUris (r:0 w:1)
+    fn set_metadata_uri() -> Weight {
+        (36_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(5 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(4 as Weight))
+    }
+    // Storage: KittiesModule AssetAuthorities (r:1 w:0)
+    // Storage: KittiesModule Kitties (r:1 w:0)
+    // Storage: KittiesModule KittyAssets (r:0 w:1)
+    fn update_kitty_asset() -> Weight {
+        (22_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Storage: KittiesModule AssetAuthorities (r:1 w:0)
+    // Storage: KittiesModule Kitties (r:1 w:0)
+    // Storage: KittiesModule KittyAssets (r:0 w:1)
+    fn update_kitty_asset_unsigned_with_signed_payload() -> Weight {
+        (20_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(2 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Storage: KittiesModule AssetAuthorities (r:1 w:1)
+    fn add_asset_authority() -> Weight {
+        (18_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+    // Storage: KittiesModule AssetAuthorities (r:1 w:1)
+    fn remove_asset_authority() -> Weight {
+        (18_000_000 as Weight)
+            .saturating_add(RocksDbWeight::get().reads(1 as Weight))
+            .saturating_add(RocksDbWeight::get().writes(1 as Weight))
+    }
+}
@@ -0,0 +1,19 @@
+//! ocw pallet 的 Runtime API，供看板查询 DOT 价格及统计
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Codec;
+
+pub use pallet_ocw::{FinalizedPrice, PriceStatistics};
+
+sp_api::decl_runtime_apis! {
+	pub trait OcwApi<BlockNumber>
+	where
+		BlockNumber: Codec,
+	{
+		/// 最近确定的价格
+		fn latest_price() -> Option<FinalizedPrice<BlockNumber>>;
+		/// 价格历史的最小值、最大值、均值、标准差，以及各窗口的时间加权平均价格
+		fn price_statistics() -> Option<PriceStatistics<BlockNumber>>;
+	}
+}
@@ -0,0 +1,69 @@
+//! 链下工作机请求的 HTTP 地址
+//!
+//! 节点运营者可以通过 `--ocw-endpoint`、`--ocw-header` 命令行参数或者 `ocw_setEndpoint` RPC，
+//! 把地址和请求头（包括 API key）写入链下持久化存储。
+//! 链下工作机每次请求前读取，没有配置时使用代码中的默认地址，
+//! 切换数据提供方不需要升级 runtime。
+//!
+//! 地址按名称区分：GitHub 信息为 [`GITHUB`]，价格源为 `PriceSource::name`。
+
+use codec::{Decode, Encode};
+use sp_runtime::{
+	offchain::{http, storage::StorageValueRef},
+	RuntimeDebug,
+};
+use sp_std::{prelude::*, str};
+
+/// GitHub 组织信息的地址名称
+pub const GITHUB: &str = "github";
+
+const KEY_PREFIX: &[u8] = b"offchain-demo::endpoint::";
+
+/// 节点为某个地址名称配置的地址及请求头
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct Endpoint {
+	/// 请求地址，为空时使用默认地址
+	pub url: Vec<u8>,
+	/// 附加的请求头 [(name, value)]
+	pub headers: Vec<(Vec<u8>, Vec<u8>)>,
+}
+
+/// 地址配置在链下持久化存储中的 key
+pub fn storage_key(name: &[u8]) -> Vec<u8> {
+	[KEY_PREFIX, name].concat()
+}
+
+impl Endpoint {
+	/// 读取节点为 `name` 配置的地址，没有配置地址时使用 `default_url`
+	pub fn load(name: &str, default_url: &str) -> Self {
+		let stored = StorageValueRef::persistent(&storage_key(name.as_bytes()))
+			.get::<Endpoint>()
+			.unwrap_or_else(|_| {
+				log::warn!("地址 {} 的配置无法解码，使用默认地址", name);
+				None
+			})
+			.unwrap_or_default();
+		let url = if stored.url.is_empty() { default_url.as_bytes().to_vec() } else { stored.url };
+		Endpoint { url, headers: stored.headers }
+	}
+
+	/// 没有配置同名请求头时加上默认的请求头
+	pub fn with_default_header(mut self, name: &str, value: &str) -> Self {
+		if !self
+			.headers
+			.iter()
+			.any(|(header, _)| header.eq_ignore_ascii_case(name.as_bytes()))
+		{
+			self.headers.push((name.as_bytes().to_vec(), value.as_bytes().to_vec()));
+		}
+		self
+	}
+
+	/// 带有全部请求头的 GET 请求，地址或请求头不是合法的 UTF-8 时返回 `None`
+	pub fn get(&self) -> Option<http::Request<'_>> {
+		let url = str::from_utf8(&self.url).ok()?;
+		self.headers.iter().try_fold(http::Request::get(url), |request, (name, value)| {
+			Some(request.add_header(str::from_utf8(name).ok()?, str::from_utf8(value).ok()?))
+		})
+	}
+}
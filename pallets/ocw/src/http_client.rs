@@ -0,0 +1,149 @@
+//! 链下工作机共用的 HTTP 请求
+//!
+//! 请求失败时在截止时间内按指数退避重试。每个地址名称有一个熔断器，连续 `FAILURE_THRESHOLD` 次
+//! 请求失败后打开，`COOL_DOWN` 内的请求直接失败；冷却结束后放行一次请求，成功则关闭熔断器，
+//! 失败则重新打开。熔断器保存在链下持久化存储中，状态变化通过日志报告。
+
+use crate::Endpoint;
+use codec::{Decode, Encode};
+use sp_core::offchain::{Duration, Timestamp};
+use sp_runtime::{offchain::storage::StorageValueRef, RuntimeDebug};
+use sp_std::prelude::*;
+
+/// 每次请求最多尝试的次数
+pub const MAX_ATTEMPTS: u32 = 3;
+/// 第一次重试前等待的时间，之后每次翻倍
+pub const INITIAL_BACKOFF: u64 = 200; // in milli-seconds
+/// 连续失败多少次后打开熔断器
+pub const FAILURE_THRESHOLD: u32 = 3;
+/// 熔断器打开的时长
+pub const COOL_DOWN: u64 = 60_000; // in milli-seconds
+
+const KEY_PREFIX: &[u8] = b"offchain-demo::breaker::";
+
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum FetchError {
+	/// 熔断器打开，没有发出请求
+	CircuitOpen,
+	/// 地址或请求头不是合法的 UTF-8
+	InvalidRequest,
+	/// 请求发送失败或者超时
+	Http,
+	/// 响应状态码不是 200
+	Status(u16),
+}
+
+impl FetchError {
+	/// 网络错误、限流和服务端错误可以重试
+	fn is_retryable(&self) -> bool {
+		matches!(self, FetchError::Http | FetchError::Status(429) | FetchError::Status(500..=599))
+	}
+}
+
+/// 地址的熔断器状态
+#[derive(Encode, Decode, Clone, Copy, Default, PartialEq, Eq, RuntimeDebug)]
+pub struct Breaker {
+	/// 连续失败的次数
+	pub failures: u32,
+	/// 熔断器打开时，在此时间（unix 毫秒）之前拒绝请求
+	pub open_until: Option<u64>,
+}
+
+impl Breaker {
+	pub fn is_open(&self, now: Timestamp) -> bool {
+		self.open_until.map_or(false, |until| now.unix_millis() < until)
+	}
+
+	pub fn record_success(&mut self, name: &str) {
+		if self.open_until.is_some() {
+			log::info!("{} 请求成功，熔断器关闭", name);
+		}
+		*self = Self::default();
+	}
+
+	pub fn record_failure(&mut self, name: &str, now: Timestamp) {
+		self.failures = self.failures.saturating_add(1);
+		if self.failures >= FAILURE_THRESHOLD {
+			self.open_until = Some(now.unix_millis().saturating_add(COOL_DOWN));
+			log::warn!("{} 连续 {} 次请求失败，熔断器打开 {} 毫秒", name, self.failures, COOL_DOWN);
+		}
+	}
+}
+
+/// 熔断器在链下持久化存储中的 key
+pub fn breaker_key(name: &str) -> Vec<u8> {
+	[KEY_PREFIX, name.as_bytes()].concat()
+}
+
+/// 读取地址的熔断器状态
+pub fn breaker(name: &str) -> Breaker {
+	StorageValueRef::persistent(&breaker_key(name))
+		.get()
+		.ok()
+		.flatten()
+		.unwrap_or_default()
+}
+
+/// 向地址名称为 `name` 的 `endpoint` 发送 GET 请求，包括重试在内不超过 `timeout`
+pub fn fetch(name: &str, endpoint: &Endpoint, timeout: Duration) -> Result<Vec<u8>, FetchError> {
+	let storage = StorageValueRef::persistent(&breaker_key(name));
+	let mut state = breaker(name);
+	let now = sp_io::offchain::timestamp();
+	if state.is_open(now) {
+		log::warn!("{} 的熔断器打开，跳过请求", name);
+		return Err(FetchError::CircuitOpen)
+	}
+
+	let result = retry(now.add(timeout), |deadline| send(endpoint, deadline));
+	match result {
+		Ok(_) => state.record_success(name),
+		Err(e) => {
+			log::warn!("{} 请求失败: {:?}", name, e);
+			state.record_failure(name, sp_io::offchain::timestamp());
+		},
+	}
+	storage.set(&state);
+	result
+}
+
+/// 在 `deadline` 前最多尝试 `MAX_ATTEMPTS` 次，失败后等待 `INITIAL_BACKOFF`
+/// 再重试，等待时间每次翻倍； 错误不能重试或者等待后会超过截止时间时直接返回错误
+pub fn retry<R>(
+	deadline: Timestamp,
+	mut attempt: impl FnMut(Timestamp) -> Result<R, FetchError>,
+) -> Result<R, FetchError> {
+	let mut backoff = INITIAL_BACKOFF;
+	let mut attempts = 1;
+	loop {
+		let error = match attempt(deadline) {
+			Ok(response) => return Ok(response),
+			Err(e) => e,
+		};
+		let retry_at = sp_io::offchain::timestamp().add(Duration::from_millis(backoff));
+		if !error.is_retryable() || attempts >= MAX_ATTEMPTS || retry_at >= deadline {
+			return Err(error)
+		}
+		log::debug!("第 {} 次请求失败: {:?}，{} 毫秒后重试", attempts, error, backoff);
+		sp_io::offchain::sleep_until(retry_at);
+		backoff = backoff.saturating_mul(2);
+		attempts += 1;
+	}
+}
+
+fn send(endpoint: &Endpoint, deadline: Timestamp) -> Result<Vec<u8>, FetchError> {
+	let pending = endpoint
+		.get()
+		.ok_or(FetchError::InvalidRequest)?
+		.deadline(deadline)
+		.send()
+		.map_err(|_| FetchError::Http)?;
+	// The returning value here is a `Result` of `Result`, so we are unwrapping it twice
+	let response = pending
+		.try_wait(deadline)
+		.map_err(|_| FetchError::Http)?
+		.map_err(|_| FetchError::Http)?;
+	if response.code != 200 {
+		return Err(FetchError::Status(response.code))
+	}
+	Ok(response.body().collect())
+}
@@ -1,10 +1,28 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub use endpoint::Endpoint;
 pub use pallet::*;
+pub use price::{Price, PriceProvider, PriceSource, RunningStats, DOT_PRICE_SOURCES};
+
+pub mod endpoint;
+pub mod http_client;
+pub mod migrations;
+pub mod price;
+
+#[cfg(test)]
+mod mock;
+
+#[cfg(test)]
+mod tests;
 
 #[frame_support::pallet]
 pub mod pallet {
 	//! A demonstration of an offchain worker that sends onchain callbacks
+	use crate::{
+		endpoint, http_client, migrations, price, Endpoint, Price, PriceProvider, PriceSource,
+		RunningStats,
+	};
+	use codec::{Decode, Encode};
 	use core::{convert::TryInto, fmt};
 	use frame_support::pallet_prelude::*;
 	use frame_system::{
@@ -14,7 +32,7 @@ pub mod pallet {
 		},
 		pallet_prelude::*,
 	};
-	use parity_scale_codec::{Decode, Encode};
+	use scale_info::TypeInfo;
 	use sp_arithmetic::per_things::Permill;
 	use sp_core::crypto::KeyTypeId;
 	use sp_runtime::{
@@ -23,7 +41,10 @@ pub mod pallet {
 			storage::StorageValueRef,
 			storage_lock::{BlockAndTime, StorageLock},
 		},
-		traits::BlockNumberProvider,
+		traits::{
+			AtLeast32BitUnsigned, BlockNumberProvider, IdentifyAccount, SaturatedConversion,
+			Saturating, Zero,
+		},
 		transaction_validity::{
 			InvalidTransaction, TransactionSource, TransactionValidity, ValidTransaction,
 		},
@@ -31,7 +52,7 @@ pub mod pallet {
 	};
 	use sp_std::{collections::vec_deque::VecDeque, prelude::*, str};
 
-	use serde::{Deserialize, Deserializer};
+	use serde::{Deserialize, Deserializer, Serialize};
 
 	/// Defines application identifier for crypto keys of this module.
 	///
@@ -41,19 +62,25 @@ pub mod pallet {
 	/// `KeyTypeId` via the keystore to sign the transaction.
 	/// The keys can be inserted manually via RPC (see `author_insertKey`).
 	pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"demo");
-	const NUM_VEC_LEN: usize = 10;
+	/// `Numbers` 保留的个数，价格历史的长度见 `Config::MaxPriceHistory`
+	pub(crate) const NUM_VEC_LEN: usize = 10;
 	/// The type to sign and send transactions.
 	const UNSIGNED_TXS_PRIORITY: u64 = 100;
 
-	// We are fetching information from the github public API about organization`substrate-developer-hub`.
+	// We are fetching information from the github public API about
+	// organization`substrate-developer-hub`. Node operators can override the URL and headers, see
+	// the `endpoint` module.
 	const HTTP_REMOTE_REQUEST: &str = "https://api.github.com/orgs/substrate-developer-hub";
 	const HTTP_HEADER_USER_AGENT: &str = "jimmychu0807";
 
-	const HTTP_DOT_PRICE_REMOTE_REQUEST: &str = "https://api.coincap.io/v2/assets/polkadot";
-
 	const FETCH_TIMEOUT_PERIOD: u64 = 3000; // in milli-seconds
 	const LOCK_TIMEOUT_EXPIRATION: u64 = FETCH_TIMEOUT_PERIOD + 1000; // in milli-seconds
 	const LOCK_BLOCK_EXPIRATION: u32 = 3; // in block number
+	/// 所有链下任务共用的锁，保证任务不会同时执行
+	pub(crate) const TASK_LOCK: &[u8] = b"offchain-demo::task-lock";
+
+	/// 价格改为 `FixedU128` 后存储版本为 1，增加 `PriceStats` 后为 2，增加 `TaskSchedules` 后为 3
+	const STORAGE_VERSION: StorageVersion = StorageVersion::new(3);
 
 	/// Based on the above `KeyTypeId` we need to generate a pallet-specific crypto type wrapper.
 	/// We can utilize the supported crypto kinds (`sr25519`, `ed25519` and `ecdsa`) and augment
@@ -87,10 +114,10 @@ pub mod pallet {
 		}
 	}
 
-	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 	pub struct Payload<Public> {
-		number: u64,
-		public: Public,
+		pub number: u64,
+		pub public: Public,
 	}
 
 	impl<T: SigningTypes> SignedPayload<T> for Payload<T::Public> {
@@ -99,11 +126,28 @@ pub mod pallet {
 		}
 	}
 
-	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+	/// 单个价格源报告的价格
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct SourcePrice {
+		/// 价格源名称
+		pub source: Vec<u8>,
+		pub price: Price,
+		/// 是否作为离群值被剔除
+		pub outlier: bool,
+	}
+
+	/// 价格轮次编号
+	pub type RoundIndex = u32;
+
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
 	pub struct PricePayload<Public, BlockNumber> {
-		block_number: BlockNumber,
-		parsed_price: (u64, Permill),
-		public: Public,
+		pub block_number: BlockNumber,
+		/// 提交价格所属的轮次
+		pub round: RoundIndex,
+		/// 各价格源聚合后的价格
+		pub parsed_price: Price,
+		pub sources: Vec<SourcePrice>,
+		pub public: Public,
 	}
 
 	impl<T: SigningTypes> SignedPayload<T> for PricePayload<T::Public, T::BlockNumber> {
@@ -112,6 +156,87 @@ pub mod pallet {
 		}
 	}
 
+	/// 预言机在某一轮提交的价格及各价格源明细
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct PriceSubmission {
+		pub price: Price,
+		pub sources: Vec<SourcePrice>,
+	}
+
+	/// 一轮提交达到法定人数后确定的价格
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct FinalizedPrice<BlockNumber> {
+		pub round: RoundIndex,
+		/// 确定价格时的区块
+		pub block_number: BlockNumber,
+		/// 本轮所有提交的中位数
+		pub price: Price,
+	}
+
+	/// 链下工作机执行的任务
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub enum OffchainTask {
+		/// 用签名交易提交数字
+		SubmitNumberSigned,
+		/// 用无签名交易提交数字
+		SubmitNumberUnsigned,
+		/// 用带签名 payload 的无签名交易提交数字
+		SubmitNumberSignedPayload,
+		/// 获取 GitHub 组织信息
+		FetchGithubInfo,
+		/// 获取并提交 DOT 价格
+		FetchPrice,
+	}
+
+	impl OffchainTask {
+		/// 所有任务，同一区块内按此顺序执行
+		pub const ALL: [OffchainTask; 5] = [
+			OffchainTask::SubmitNumberSigned,
+			OffchainTask::SubmitNumberUnsigned,
+			OffchainTask::SubmitNumberSignedPayload,
+			OffchainTask::FetchGithubInfo,
+			OffchainTask::FetchPrice,
+		];
+
+		/// 默认每 5 个区块轮流执行一个任务
+		pub fn default_schedule<BlockNumber: From<u32>>(self) -> TaskSchedule<BlockNumber> {
+			TaskSchedule { period: 5u32.into(), offset: (self as u32).into(), enabled: true }
+		}
+	}
+
+	/// 任务计划：区块号减去 `offset` 后能被 `period` 整除时执行
+	#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+	pub struct TaskSchedule<BlockNumber> {
+		pub period: BlockNumber,
+		pub offset: BlockNumber,
+		pub enabled: bool,
+	}
+
+	impl<BlockNumber: AtLeast32BitUnsigned + Copy> TaskSchedule<BlockNumber> {
+		pub fn is_due(&self, block_number: BlockNumber) -> bool {
+			self.enabled &&
+				!self.period.is_zero() &&
+				block_number >= self.offset &&
+				((block_number - self.offset) % self.period).is_zero()
+		}
+	}
+
+	/// 供看板使用的价格统计
+	#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+	pub struct PriceStatistics<BlockNumber> {
+		pub latest: FinalizedPrice<BlockNumber>,
+		/// 统计的价格个数，即 `Prices` 的长度
+		pub count: u32,
+		pub min: Price,
+		pub max: Price,
+		pub mean: Price,
+		pub std_dev: Price,
+		/// `Config::TwapWindows` 中每个窗口的时间加权平均价格 [(window, twap)]
+		pub twaps: Vec<(BlockNumber, Price)>,
+	}
+
 	// ref: https://serde.rs/container-attrs.html#crate
 	#[derive(Deserialize, Encode, Decode, Default)]
 	struct GithubInfo {
@@ -123,20 +248,6 @@ pub mod pallet {
 		public_repos: u32,
 	}
 
-	#[derive(Deserialize, Encode, Decode, Default)]
-	struct PriceInfo {
-		#[serde(deserialize_with = "de_string_to_bytes")]
-		price_usd: Vec<u8>,
-	}
-
-	impl fmt::Debug for PriceInfo {
-		// `fmt` converts the vector of bytes inside the struct back to string for
-		//   more friendly display.
-		fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-			write!(f, "{{ priceUsd:{} }}", str::from_utf8(&self.price_usd).map_err(|_| fmt::Error)?,)
-		}
-	}
-
 	#[derive(Debug, Deserialize, Encode, Decode, Default)]
 	struct IndexingData(Vec<u8>, u64);
 
@@ -170,10 +281,36 @@ pub mod pallet {
 		type Call: From<Call<Self>>;
 		/// The identifier type for an offchain worker.
 		type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
+		/// DOT/USD 价格源列表
+		type PriceSources: Get<&'static [PriceSource]>;
+		/// 价格偏离所有价格源中位数超过该比例时视为离群值
+		#[pallet::constant]
+		type MaxPriceDeviation: Get<Permill>;
+		/// `Prices` 保留的轮数
+		#[pallet::constant]
+		type MaxPriceHistory: Get<u32>;
+		/// 统计时间加权平均价格的窗口，单位为区块
+		#[pallet::constant]
+		type TwapWindows: Get<Vec<Self::BlockNumber>>;
+		/// 一轮中确定最终价格所需的最少预言机提交数，实际法定人数不低于预言机集合的过半数
+		#[pallet::constant]
+		type PriceQuorum: Get<u32>;
+		/// 修改链下任务计划的权限，一般为 root
+		type ScheduleOrigin: EnsureOrigin<Self::Origin>;
+		/// 管理预言机集合的权限，一般为 root
+		type OracleOrigin: EnsureOrigin<Self::Origin>;
+		/// 预言机数量上限
+		#[pallet::constant]
+		type MaxOracleAuthorities: Get<u32>;
+		/// 一轮开启后超过该区块数仍未达到法定人数时作废，开启下一轮
+		#[pallet::constant]
+		type RoundTimeout: Get<Self::BlockNumber>;
 	}
 
 	#[pallet::pallet]
 	#[pallet::generate_store(pub(super) trait Store)]
+	#[pallet::storage_version(STORAGE_VERSION)]
+	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
 	// The pallet's runtime storage items.
@@ -184,15 +321,107 @@ pub mod pallet {
 	// https://substrate.dev/docs/en/knowledgebase/runtime/storage#declaring-storage-items
 	pub type Numbers<T> = StorageValue<_, VecDeque<u64>, ValueQuery>;
 
+	/// 最近确定的价格，只保留 `MaxPriceHistory` 轮
 	#[pallet::storage]
 	#[pallet::getter(fn prices)]
-	pub type Prices<T> = StorageValue<_, VecDeque<(u64, Permill)>, ValueQuery>;
+	pub type Prices<T: Config> =
+		StorageValue<_, VecDeque<FinalizedPrice<T::BlockNumber>>, ValueQuery>;
+
+	/// `Prices` 中价格的统计量，随 `Prices` 增量更新
+	#[pallet::storage]
+	#[pallet::getter(fn price_stats)]
+	pub type PriceStats<T> = StorageValue<_, RunningStats, ValueQuery>;
+
+	/// 当前接受提交的轮次
+	#[pallet::storage]
+	#[pallet::getter(fn current_round)]
+	pub type CurrentRound<T> = StorageValue<_, RoundIndex, ValueQuery>;
+
+	/// 当前轮次开启的区块，没有时从下一个区块开始计时
+	#[pallet::storage]
+	#[pallet::getter(fn round_started_at)]
+	pub type RoundStartedAt<T: Config> = StorageValue<_, T::BlockNumber>;
+
+	/// 每轮中每个预言机提交的价格，随对应轮次的价格一起从 `Prices` 中淘汰
+	#[pallet::storage]
+	#[pallet::getter(fn round_submissions)]
+	pub type RoundSubmissions<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		RoundIndex,
+		Blake2_128Concat,
+		T::AccountId,
+		PriceSubmission,
+	>;
+
+	/// 链下任务的执行计划，没有计划的任务不会执行
+	#[pallet::storage]
+	#[pallet::getter(fn task_schedules)]
+	pub type TaskSchedules<T: Config> =
+		StorageMap<_, Twox64Concat, OffchainTask, TaskSchedule<T::BlockNumber>>;
+
+	/// 允许提交价格的预言机
+	#[pallet::storage]
+	#[pallet::getter(fn oracle_authorities)]
+	pub type OracleAuthorities<T: Config> =
+		StorageValue<_, BoundedVec<T::AccountId, T::MaxOracleAuthorities>, ValueQuery>;
+
+	/// 每个预言机累计提交价格的次数，用于监控，移出预言机集合时清除
+	#[pallet::storage]
+	#[pallet::getter(fn oracle_submission_count)]
+	pub type OracleSubmissionCount<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		pub oracle_authorities: Vec<T::AccountId>,
+		pub task_schedules: Vec<(OffchainTask, TaskSchedule<T::BlockNumber>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self {
+				oracle_authorities: Vec::new(),
+				task_schedules: OffchainTask::ALL
+					.into_iter()
+					.map(|task| (task, task.default_schedule()))
+					.collect(),
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			let authorities: BoundedVec<_, _> = self
+				.oracle_authorities
+				.clone()
+				.try_into()
+				.expect("too many genesis oracle authorities");
+			OracleAuthorities::<T>::put(authorities);
+			for (task, schedule) in &self.task_schedules {
+				TaskSchedules::<T>::insert(task, schedule);
+			}
+		}
+	}
 
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
 		NewNumber(Option<T::AccountId>, u64),
-		NewPrice(Option<T::AccountId>, (u64, Permill)),
+		/// 预言机提交了某一轮的价格 [oracle, round, price]
+		PriceSubmitted(T::AccountId, RoundIndex, Price),
+		/// 一轮提交达到法定人数，确定了最终价格 [round, block_number, price]
+		PriceFinalized(RoundIndex, T::BlockNumber, Price),
+		/// 加入预言机集合 [oracle]
+		OracleAuthorityAdded(T::AccountId),
+		/// 移出预言机集合 [oracle]
+		OracleAuthorityRemoved(T::AccountId),
+		/// 修改了链下任务计划 [task, schedule]
+		TaskScheduleUpdated(OffchainTask, TaskSchedule<T::BlockNumber>),
+		/// 一轮超时未达到法定人数，本轮提交作废 [round, block_number]
+		RoundExpired(RoundIndex, T::BlockNumber),
 	}
 
 	// Errors inform users that something went wrong.
@@ -214,11 +443,55 @@ pub mod pallet {
 		// Error returned when fetching github info
 		HttpFetchingError,
 		ParseError,
+		// 没有可用的价格源，或者所有价格都是离群值
+		NoValidPrice,
 		NoLocalAccountForSigning,
+		// 提交的价格不属于当前轮次
+		StaleRound,
+		// 预言机在本轮已经提交过价格
+		DuplicateSubmission,
+		// 提交者不在预言机集合中
+		NotOracleAuthority,
+		// 已经在预言机集合中
+		AlreadyOracleAuthority,
+		// 预言机数量达到上限
+		TooManyOracleAuthorities,
+		// 任务周期不能为 0
+		InvalidSchedule,
+		// 地址连续请求失败，熔断器打开
+		CircuitBreakerOpen,
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// 当前轮次超时后作废，避免预言机不足时价格永远无法更新
+		fn on_initialize(block_number: T::BlockNumber) -> Weight {
+			let started_at = match Self::round_started_at() {
+				Some(started_at) => started_at,
+				None => {
+					RoundStartedAt::<T>::put(block_number);
+					return T::DbWeight::get().reads_writes(1, 1)
+				},
+			};
+			if block_number.saturating_sub(started_at) < T::RoundTimeout::get() {
+				return T::DbWeight::get().reads(1)
+			}
+
+			let round = Self::current_round();
+			// 移出预言机后法定人数可能降低，已有的提交足够时仍然确定价格
+			if !Self::try_finalize_round(round) {
+				Self::expire_round(round, block_number);
+			}
+			let submissions = T::MaxOracleAuthorities::get() as u64;
+			T::DbWeight::get().reads_writes(submissions + 3, submissions + 3)
+		}
+
+		fn on_runtime_upgrade() -> Weight {
+			migrations::v1::migrate::<T>()
+				.saturating_add(migrations::v2::migrate::<T>())
+				.saturating_add(migrations::v3::migrate::<T>())
+		}
+
 		/// Offchain Worker entry point.
 		///
 		/// By implementing `fn offchain_worker` you declare a new offchain worker.
@@ -236,19 +509,54 @@ pub mod pallet {
 			// 2. Sending unsigned transaction from ocw
 			// 3. Sending unsigned transactions with signed payloads from ocw
 			// 4. Fetching JSON via http requests in ocw
-			const TX_TYPES: u32 = 5;
-			let modu = block_number.try_into().map_or(TX_TYPES, |bn: usize| (bn as u32) % TX_TYPES);
-			let result = match modu {
-				0 => Self::offchain_signed_tx(block_number),
-				1 => Self::offchain_unsigned_tx(block_number),
-				2 => Self::offchain_unsigned_tx_signed_payload(block_number),
-				3 => Self::fetch_github_info(),
-				4 => Self::fetch_price_info(block_number),
-				_ => Err(Error::<T>::UnknownOffchainMux),
+			// 每个任务按 `TaskSchedules` 中的计划执行
+			let due_tasks: Vec<_> = OffchainTask::ALL
+				.into_iter()
+				.filter(|task| Self::task_schedules(task).map_or(false, |s| s.is_due(block_number)))
+				.collect();
+			if due_tasks.is_empty() {
+				return
+			}
+
+			// Since off-chain storage can be accessed by off-chain workers from multiple runs, it is important to lock
+			//   it before doing heavy computations or write operations.
+			//
+			// There are four ways of defining a lock:
+			//   1) `new` - lock with default time and block exipration
+			//   2) `with_deadline` - lock with default block but custom time expiration
+			//   3) `with_block_deadline` - lock with default time but custom block expiration
+			//   4) `with_block_and_time_deadline` - lock with custom time and block expiration
+			// Here we choose the most custom one for demonstration purpose.
+			// 所有任务共用一把锁，锁的时长要覆盖 GitHub 和所有价格源的请求
+			let price_sources = T::PriceSources::get().len() as u64;
+			let mut lock = StorageLock::<BlockAndTime<Self>>::with_block_and_time_deadline(
+				TASK_LOCK,
+				LOCK_BLOCK_EXPIRATION,
+				rt_offchain::Duration::from_millis(
+					FETCH_TIMEOUT_PERIOD * price_sources + LOCK_TIMEOUT_EXPIRATION,
+				),
+			);
+			// 获取不到锁说明上一次的任务还没有结束，跳过本区块的任务
+			let _guard = match lock.try_lock() {
+				Ok(guard) => guard,
+				Err(_) => {
+					log::info!("offchain tasks still running, skip block {:?}", block_number);
+					return
+				},
 			};
 
-			if let Err(e) = result {
-				log::error!("offchain_worker error: {:?}", e);
+			for task in due_tasks {
+				let result = match task {
+					OffchainTask::SubmitNumberSigned => Self::offchain_signed_tx(block_number),
+					OffchainTask::SubmitNumberUnsigned => Self::offchain_unsigned_tx(block_number),
+					OffchainTask::SubmitNumberSignedPayload =>
+						Self::offchain_unsigned_tx_signed_payload(block_number),
+					OffchainTask::FetchGithubInfo => Self::fetch_github_info(),
+					OffchainTask::FetchPrice => Self::fetch_price_info(block_number),
+				};
+				if let Err(e) = result {
+					log::error!("offchain_worker task {:?} error: {:?}", task, e);
+				}
 			}
 		}
 	}
@@ -273,15 +581,36 @@ pub mod pallet {
 			};
 
 			match call {
-				Call::submit_number_unsigned(_number) => {
-					valid_tx(b"submit_number_unsigned".to_vec())
-				},
-				Call::submit_number_unsigned_with_signed_payload(ref payload, ref signature) => {
+				Call::submit_number_unsigned { .. } => valid_tx(b"submit_number_unsigned".to_vec()),
+				Call::submit_number_unsigned_with_signed_payload { payload, signature } => {
 					if !SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone()) {
 						return InvalidTransaction::BadProof.into();
 					}
 					valid_tx(b"submit_number_unsigned_with_signed_payload".to_vec())
 				},
+				Call::submit_price_unsigned_with_signed_payload { price_payload, signature } => {
+					if !SignedPayload::<T>::verify::<T::AuthorityId>(
+						price_payload,
+						signature.clone(),
+					) {
+						return InvalidTransaction::BadProof.into();
+					}
+					// 只接受预言机集合中的密钥
+					let who = price_payload.public.clone().into_account();
+					if !Self::is_oracle_authority(&who) {
+						return InvalidTransaction::BadSigner.into();
+					}
+					// 过期轮次或者重复的提交不进入交易池
+					if price_payload.round != Self::current_round() ||
+						RoundSubmissions::<T>::contains_key(price_payload.round, &who)
+					{
+						return InvalidTransaction::Stale.into();
+					}
+					valid_tx(
+						(b"submit_price_unsigned_with_signed_payload", price_payload.round, who)
+							.encode(),
+					)
+				},
 				_ => InvalidTransaction::Call.into(),
 			}
 		}
@@ -334,15 +663,82 @@ pub mod pallet {
 		) -> DispatchResult {
 			// 确保提交的是无签名交易
 			let _ = ensure_none(origin)?;
-			let PricePayload { block_number: _, parsed_price, public: _ } = price_payload;
-			log::info!("submit_price_unsigned_with_signed_payload");
-			Self::append_or_replace_price(parsed_price);
-			Self::deposit_event(Event::NewPrice(None, parsed_price));
+			let PricePayload { round, parsed_price, sources, public, .. } = price_payload;
+			ensure!(round == Self::current_round(), Error::<T>::StaleRound);
+			let who = public.into_account();
+			ensure!(Self::is_oracle_authority(&who), Error::<T>::NotOracleAuthority);
+			ensure!(
+				!RoundSubmissions::<T>::contains_key(round, &who),
+				Error::<T>::DuplicateSubmission
+			);
+			log::info!("submit_price_unsigned_with_signed_payload: ({}, {:?})", round, who);
+
+			RoundSubmissions::<T>::insert(
+				round,
+				&who,
+				PriceSubmission { price: parsed_price, sources },
+			);
+			OracleSubmissionCount::<T>::mutate(&who, |count| *count = count.saturating_add(1));
+			Self::deposit_event(Event::PriceSubmitted(who, round, parsed_price));
+			Self::try_finalize_round(round);
+			Ok(())
+		}
+
+		/// 修改链下任务的执行计划
+		#[pallet::weight(10000)]
+		pub fn set_task_schedule(
+			origin: OriginFor<T>,
+			task: OffchainTask,
+			schedule: TaskSchedule<T::BlockNumber>,
+		) -> DispatchResult {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			ensure!(!schedule.period.is_zero(), Error::<T>::InvalidSchedule);
+			TaskSchedules::<T>::insert(task, schedule);
+
+			Self::deposit_event(Event::TaskScheduleUpdated(task, schedule));
+			Ok(())
+		}
+
+		/// 把账户加入预言机集合
+		#[pallet::weight(10000)]
+		pub fn add_oracle_authority(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::OracleOrigin::ensure_origin(origin)?;
+			OracleAuthorities::<T>::try_mutate(|authorities| -> DispatchResult {
+				ensure!(!authorities.contains(&who), Error::<T>::AlreadyOracleAuthority);
+				authorities
+					.try_push(who.clone())
+					.map_err(|_| Error::<T>::TooManyOracleAuthorities)?;
+				Ok(())
+			})?;
+
+			Self::deposit_event(Event::OracleAuthorityAdded(who));
+			Ok(())
+		}
+
+		/// 把账户移出预言机集合，已经提交的价格仍然有效
+		#[pallet::weight(10000)]
+		pub fn remove_oracle_authority(origin: OriginFor<T>, who: T::AccountId) -> DispatchResult {
+			T::OracleOrigin::ensure_origin(origin)?;
+			OracleAuthorities::<T>::try_mutate(|authorities| -> DispatchResult {
+				let index = authorities
+					.iter()
+					.position(|authority| authority == &who)
+					.ok_or(Error::<T>::NotOracleAuthority)?;
+				authorities.remove(index);
+				Ok(())
+			})?;
+			OracleSubmissionCount::<T>::remove(&who);
+
+			Self::deposit_event(Event::OracleAuthorityRemoved(who));
 			Ok(())
 		}
 	}
 
 	impl<T: Config> Pallet<T> {
+		pub fn is_oracle_authority(who: &T::AccountId) -> bool {
+			Self::oracle_authorities().contains(who)
+		}
+
 		/// Append a new number to the tail of the list, removing an element from the head if reaching
 		///   the bounded length.
 		fn append_or_replace_number(number: u64) {
@@ -355,70 +751,125 @@ pub mod pallet {
 			});
 		}
 
-		fn append_or_replace_price(price: (u64, Permill)) {
+		/// 法定人数：不少于 `PriceQuorum`，并且超过预言机集合的半数
+		pub fn price_quorum() -> u32 {
+			let majority = Self::oracle_authorities().len() as u32 / 2 + 1;
+			T::PriceQuorum::get().max(majority)
+		}
+
+		/// 提交数达到法定人数时，取本轮所有提交的中位数作为最终价格，并开启下一轮。
+		/// 返回是否确定了价格
+		fn try_finalize_round(round: RoundIndex) -> bool {
+			let prices: Vec<_> =
+				RoundSubmissions::<T>::iter_prefix_values(round).map(|s| s.price).collect();
+			if (prices.len() as u32) < Self::price_quorum() {
+				return false
+			}
+			match price::median_price(&prices) {
+				Some(price) => {
+					let block_number = <frame_system::Pallet<T>>::block_number();
+					Self::append_or_replace_price(FinalizedPrice { round, block_number, price });
+					Self::start_round(round.saturating_add(1), block_number);
+					Self::deposit_event(Event::PriceFinalized(round, block_number, price));
+					true
+				},
+				None => false,
+			}
+		}
+
+		/// 作废超时的轮次：丢弃本轮的提交，开启下一轮
+		fn expire_round(round: RoundIndex, block_number: T::BlockNumber) {
+			let _ = RoundSubmissions::<T>::clear_prefix(round, u32::MAX, None);
+			Self::start_round(round.saturating_add(1), block_number);
+			Self::deposit_event(Event::RoundExpired(round, block_number));
+		}
+
+		fn start_round(round: RoundIndex, block_number: T::BlockNumber) {
+			CurrentRound::<T>::put(round);
+			RoundStartedAt::<T>::put(block_number);
+		}
+
+		pub(crate) fn append_or_replace_price(price: FinalizedPrice<T::BlockNumber>) {
+			let max_history = T::MaxPriceHistory::get().max(1) as usize;
 			Prices::<T>::mutate(|prices| {
-				// 当长度达到10
-				if prices.len() == NUM_VEC_LEN {
-					// 丢弃最早的值
-					let _ = prices.pop_front();
-				}
-				// 写入新的值
+				PriceStats::<T>::mutate(|stats| {
+					// 长度达到上限时丢弃最早的值，以及该轮各预言机的提交；
+					// 上限调小后可能需要丢弃多个
+					while prices.len() >= max_history {
+						if let Some(expired) = prices.pop_front() {
+							let _ =
+								RoundSubmissions::<T>::clear_prefix(expired.round, u32::MAX, None);
+							stats.remove(expired.price, prices.iter().map(|p| p.price));
+						}
+					}
+					// 写入新的值
+					stats.insert(price.price);
+				});
 				prices.push_back(price);
 				log::info!("Prices vector: {:?}", prices);
 			});
 		}
 
+		/// 最新价格及 `Prices` 的统计，没有价格时返回 `None`
+		pub fn price_statistics() -> Option<PriceStatistics<T::BlockNumber>> {
+			let latest = *Self::prices().back()?;
+			let stats = Self::price_stats();
+			let twaps = T::TwapWindows::get()
+				.into_iter()
+				.filter_map(|window| Some((window, Self::twap(window)?)))
+				.collect();
+			Some(PriceStatistics {
+				latest,
+				count: stats.count,
+				min: stats.min,
+				max: stats.max,
+				mean: stats.mean()?,
+				std_dev: stats.std_dev()?,
+				twaps,
+			})
+		}
+
 		fn fetch_price_info(block_number: T::BlockNumber) -> Result<(), Error<T>> {
 			// 利用 offchain worker 取出 DOT 当前对 USD 的价格，并把写到一个 Vec 的存储里，
 			// 你们自己选一种方法提交回链上，并在代码注释为什么用这种方法提交回链上最好。只保留当前最近的 10 个价格，
 			// 其他价格可丢弃 （就是 Vec 的长度长到 10 后，这时再插入一个值时，要先丢弃最早的那个值）。
 
 			// 取得的价格 parse 完后，放在以下存儲：
-			// pub type Prices<T> = StorageValue<_, VecDeque<(u64, Permill)>, ValueQuery>
+			// pub type Prices<T> = StorageValue<_, VecDeque<FinalizedPrice<T::BlockNumber>>,
+			// ValueQuery>
 
-			// 这个 http 请求可得到当前 DOT 价格：
+			// DOT USD 价格来自 `Config::PriceSources` 中的多个价格源，例如
 			// [https://api.coincap.io/v2/assets/polkadot](https://api.coincap.io/v2/assets/polkadot)。
-			// 获取DOT USD价格
-
-			// 存储对象
-			let s_info = StorageValueRef::persistent(b"offchain-demo::price-info");
 
-			// 如果缓存存在，输出并返回
-			if let Ok(Some(price_info)) = s_info.get::<PriceInfo>() {
-				log::info!("cached price-info: {:?}", price_info);
-				return Ok(());
-			}
-			// 创建会自动过期的锁，防止多个实例同时访问
-			let mut lock = StorageLock::<BlockAndTime<Self>>::with_block_and_time_deadline(
-				b"offchain-demo::price-lock",
-				LOCK_BLOCK_EXPIRATION,
-				rt_offchain::Duration::from_millis(LOCK_TIMEOUT_EXPIRATION),
-			);
-			// 尝试获取锁
-			if let Ok(_guard) = lock.try_lock() {
-				// 获取成功，获取价格
-				match Self::fetch_price_parse() {
-					Ok(price_info) => {
-						// 写入价格信息
-						s_info.set(&price_info);
-					},
-					Err(err) => {
-						return Err(err);
+			// 价格每次都要重新获取，`offchain_worker` 中的任务锁保证不会同时请求
+			let fetched: Vec<_> = T::PriceSources::get()
+				.iter()
+				.filter_map(|source| match Self::fetch_source_price(source) {
+					Ok(price) => Some((source, price)),
+					Err(e) => {
+						log::warn!("价格源 {} 获取失败: {:?}", source.name, e);
+						None
 					},
-				}
-			}
-			// 获取价格信息
-			let price_info = s_info.get::<PriceInfo>().unwrap().unwrap();
-			// 将价格转换为字符串
-			let price_str = str::from_utf8(&price_info.price_usd).map_err(|_| {
-				log::warn!("解析失败");
-				<Error<T>>::ParseError
-			})?;
-			// 将价格字符串转换为符合要求的格式
-			let parsed_price = match Self::parse_price(&price_str) {
-				Some(parsed_price) => Ok(parsed_price),
-				None => Err(<Error<T>>::ParseError),
-			}?;
+				})
+				.collect();
+
+			// 剔除离群值后取中位数
+			let prices: Vec<_> = fetched.iter().map(|(_, price)| *price).collect();
+			let (parsed_price, outliers) = price::aggregate(&prices, T::MaxPriceDeviation::get())
+				.ok_or(<Error<T>>::NoValidPrice)?;
+			let sources: Vec<_> = fetched
+				.iter()
+				.zip(outliers)
+				.map(|((source, price), outlier)| SourcePrice {
+					source: source.name.as_bytes().to_vec(),
+					price: *price,
+					outlier,
+				})
+				.collect();
+			log::info!("aggregated price: {:?}, sources: {:?}", parsed_price, sources);
+
+			// 提交到当前轮次，链上凑齐法定人数后取中位数
+			let round = Self::current_round();
 
 			// 使用无签名交易（带有签名payload）方法，将数据提交到链上
 			// 1. 价格数据和特定账户没有关联，无签名交易可以避免手续费
@@ -427,10 +878,15 @@ pub mod pallet {
 			if let Some((_, res)) = signer.send_unsigned_transaction(
 				|account| PricePayload {
 					block_number,
+					round,
 					parsed_price,
+					sources: sources.clone(),
 					public: account.public.clone(),
 				},
-				Call::submit_price_unsigned_with_signed_payload,
+				|price_payload, signature| Call::submit_price_unsigned_with_signed_payload {
+					price_payload,
+					signature,
+				},
 			) {
 				return res.map_err(|_| {
 					log::error!("Failed in offchain_unsigned_tx_signed_payload");
@@ -441,70 +897,35 @@ pub mod pallet {
 			Err(<Error<T>>::NoLocalAccountForSigning)
 		}
 
-		fn parse_price(price_str: &str) -> Option<(u64, Permill)> {
-			// 字符串转换为数组
-			let price = price_str.as_bytes();
-			// 获取小数点位置
-			let mut pos = 0;
-			for (i, &item) in price.iter().enumerate() {
-				if item == b'.' {
-					pos = i;
-					break;
-				}
-			}
-			// 整数部分
-			let integer = &price[0..pos];
-			// 小数部分
-			let fraction = &price[pos + 1..price.len()];
-			// 包装成字符串
-			let integer_str = str::from_utf8(integer).unwrap();
-			let fraction_str = str::from_utf8(fraction).unwrap();
-			// 将字符串转换为数字
-			let price_integer: u64 = integer_str.parse().unwrap();
-			let price_fraction = Permill::from_parts(fraction_str.parse().unwrap());
-			Some((price_integer, price_fraction))
-		}
-
-		fn fetch_price_parse() -> Result<PriceInfo, Error<T>> {
-			// 发起请求获取价格信息response
-			let resp_bytes = Self::fetch_price_from_remote().map_err(|e| {
-				log::error!("fetch_from_remote error: {:?}", e);
-				<Error<T>>::HttpFetchingError
-			})?;
-			// 将流转换为字符串
-			let resp_str =
-				str::from_utf8(&resp_bytes).map_err(|_| <Error<T>>::HttpFetchingError)?;
-			// Print out our fetched JSON string
-			log::info!("{}", resp_str);
-			// 将字符串转换为对象
-			let price_info: PriceInfo =
-				serde_json::from_str(&resp_str).map_err(|_| <Error<T>>::HttpFetchingError)?;
-			Ok(price_info)
-		}
-
-		fn fetch_price_from_remote() -> Result<Vec<u8>, Error<T>> {
-			log::info!("发送请求到：{}", HTTP_DOT_PRICE_REMOTE_REQUEST);
-			//创建一个GET请求
-			let request = rt_offchain::http::Request::get(HTTP_DOT_PRICE_REMOTE_REQUEST);
-			// 将调用时间限制在3s之内
-			let timeout = sp_io::offchain::timestamp()
-				.add(rt_offchain::Duration::from_millis(FETCH_TIMEOUT_PERIOD));
-			// 发起GET请求
-			let pending = request
-				.deadline(timeout) // Setting the timeout time
-				.send() // Sending the request out by the host
-				.map_err(|_| <Error<T>>::HttpFetchingError)?;
-			// 等待请求响应
-			let response = pending
-				.try_wait(timeout)
-				.map_err(|_| <Error<T>>::HttpFetchingError)?
-				.map_err(|_| <Error<T>>::HttpFetchingError)?;
-			// 检查是否响应成功
-			if response.code != 200 {
-				log::error!("Unexpected http request status code: {}", response.code);
-				return Err(<Error<T>>::HttpFetchingError);
-			}
-			Ok(response.body().collect::<Vec<u8>>())
+		/// 请求单个价格源，按其 JSON 路径取出并解析价格
+		fn fetch_source_price(source: &PriceSource) -> Result<Price, Error<T>> {
+			// 节点可以为每个价格源配置地址和 API key
+			let resp_bytes = Self::fetch_price_from_remote(
+				source.name,
+				&Endpoint::load(source.name, source.url),
+			)?;
+			let price_bytes = price::extract_price(&resp_bytes, source.json_path)
+				.ok_or(<Error<T>>::ParseError)?;
+			let price_str = str::from_utf8(&price_bytes).map_err(|_| <Error<T>>::ParseError)?;
+			price::parse_price(price_str).map_err(|e| {
+				log::warn!("价格 {} 解析失败: {:?}", price_str, e);
+				<Error<T>>::ParseError
+			})
+		}
+
+		fn fetch_price_from_remote(name: &str, endpoint: &Endpoint) -> Result<Vec<u8>, Error<T>> {
+			// 请求头中可能有 API key，只打印地址
+			log::info!("发送请求到：{}", str::from_utf8(&endpoint.url).unwrap_or_default());
+			Self::fetch_endpoint(name, endpoint)
+		}
+
+		/// 通过共用的 HTTP 客户端请求，失败时在 3s 内重试
+		fn fetch_endpoint(name: &str, endpoint: &Endpoint) -> Result<Vec<u8>, Error<T>> {
+			let timeout = rt_offchain::Duration::from_millis(FETCH_TIMEOUT_PERIOD);
+			http_client::fetch(name, endpoint, timeout).map_err(|e| match e {
+				http_client::FetchError::CircuitOpen => <Error<T>>::CircuitBreakerOpen,
+				_ => <Error<T>>::HttpFetchingError,
+			})
 		}
 
 		/// Check if we have fetched github info before. If yes, we can use the cached version
@@ -530,32 +951,15 @@ pub mod pallet {
 				return Ok(());
 			}
 
-			// Since off-chain storage can be accessed by off-chain workers from multiple runs, it is important to lock
-			//   it before doing heavy computations or write operations.
-			//
-			// There are four ways of defining a lock:
-			//   1) `new` - lock with default time and block exipration
-			//   2) `with_deadline` - lock with default block but custom time expiration
-			//   3) `with_block_deadline` - lock with default time but custom block expiration
-			//   4) `with_block_and_time_deadline` - lock with custom time and block expiration
-			// Here we choose the most custom one for demonstration purpose.
-			let mut lock = StorageLock::<BlockAndTime<Self>>::with_block_and_time_deadline(
-				b"offchain-demo::lock",
-				LOCK_BLOCK_EXPIRATION,
-				rt_offchain::Duration::from_millis(LOCK_TIMEOUT_EXPIRATION),
-			);
-
-			// We try to acquire the lock here. If failed, we know the `fetch_n_parse` part inside is being
-			//   executed by previous run of ocw, so the function just returns.
-			if let Ok(_guard) = lock.try_lock() {
-				match Self::fetch_n_parse() {
-					Ok(gh_info) => {
-						s_info.set(&gh_info);
-					},
-					Err(err) => {
-						return Err(err);
-					},
-				}
+			// The task lock acquired in `offchain_worker` guarantees that `fetch_n_parse` is not
+			//   being executed by a previous run of ocw at the same time.
+			match Self::fetch_n_parse() {
+				Ok(gh_info) => {
+					s_info.set(&gh_info);
+				},
+				Err(err) => {
+					return Err(err);
+				},
 			}
 			Ok(())
 		}
@@ -564,7 +968,7 @@ pub mod pallet {
 		fn fetch_n_parse() -> Result<GithubInfo, Error<T>> {
 			let resp_bytes = Self::fetch_from_remote().map_err(|e| {
 				log::error!("fetch_from_remote error: {:?}", e);
-				<Error<T>>::HttpFetchingError
+				e
 			})?;
 
 			let resp_str =
@@ -578,42 +982,19 @@ pub mod pallet {
 			Ok(gh_info)
 		}
 
-		/// This function uses the `offchain::http` API to query the remote github information,
+		/// This function uses the shared `http_client` to query the remote github information,
 		///   and returns the JSON response as vector of bytes.
 		fn fetch_from_remote() -> Result<Vec<u8>, Error<T>> {
-			log::info!("sending request to: {}", HTTP_REMOTE_REQUEST);
-
-			// Initiate an external HTTP GET request. This is using high-level wrappers from `sp_runtime`.
-			let request = rt_offchain::http::Request::get(HTTP_REMOTE_REQUEST);
-
-			// Keeping the offchain worker execution time reasonable, so limiting the call to be within 3s.
-			let timeout = sp_io::offchain::timestamp()
-				.add(rt_offchain::Duration::from_millis(FETCH_TIMEOUT_PERIOD));
-
 			// For github API request, we also need to specify `user-agent` in http request header.
 			//   See: https://developer.github.com/v3/#user-agent-required
-			let pending = request
-				.add_header("User-Agent", HTTP_HEADER_USER_AGENT)
-				.deadline(timeout) // Setting the timeout time
-				.send() // Sending the request out by the host
-				.map_err(|_| <Error<T>>::HttpFetchingError)?;
-
-			// By default, the http request is async from the runtime perspective. So we are asking the
-			//   runtime to wait here.
-			// The returning value here is a `Result` of `Result`, so we are unwrapping it twice by two `?`
-			//   ref: https://substrate.dev/rustdocs/v2.0.0/sp_runtime/offchain/http/struct.PendingRequest.html#method.try_wait
-			let response = pending
-				.try_wait(timeout)
-				.map_err(|_| <Error<T>>::HttpFetchingError)?
-				.map_err(|_| <Error<T>>::HttpFetchingError)?;
-
-			if response.code != 200 {
-				log::error!("Unexpected http request status code: {}", response.code);
-				return Err(<Error<T>>::HttpFetchingError);
-			}
-
-			// Next we fully read the response body and collect it to a vector of bytes.
-			Ok(response.body().collect::<Vec<u8>>())
+			let endpoint = Endpoint::load(endpoint::GITHUB, HTTP_REMOTE_REQUEST)
+				.with_default_header("User-Agent", HTTP_HEADER_USER_AGENT);
+			log::info!("sending request to: {}", str::from_utf8(&endpoint.url).unwrap_or_default());
+
+			// The shared http client retries failed requests with exponential backoff, keeping the
+			//   offchain worker execution time reasonable by limiting all attempts to be within 3s.
+			//   Repeated failures open a circuit breaker, skipping the endpoint for a while.
+			Self::fetch_endpoint(endpoint::GITHUB, &endpoint)
 		}
 
 		fn offchain_signed_tx(block_number: T::BlockNumber) -> Result<(), Error<T>> {
@@ -631,7 +1012,7 @@ pub mod pallet {
 			//   - `Some((account, Err(())))`: error occured when sending the transaction
 			let result = signer.send_signed_transaction(|_acct|
 				// This is the on-chain function
-				Call::submit_number_signed(number));
+				Call::submit_number_signed { number });
 
 			// Display error if the signed tx fails.
 			if let Some((acc, res)) = result {
@@ -650,7 +1031,7 @@ pub mod pallet {
 
 		fn offchain_unsigned_tx(block_number: T::BlockNumber) -> Result<(), Error<T>> {
 			let number: u64 = block_number.try_into().unwrap_or(0);
-			let call = Call::submit_number_unsigned(number);
+			let call = Call::submit_number_unsigned { number };
 
 			// `submit_unsigned_transaction` returns a type of `Result<(), ()>`
 			//   ref: https://substrate.dev/rustdocs/v2.0.0/frame_system/offchain/struct.SubmitTransaction.html#method.submit_unsigned_transaction
@@ -677,7 +1058,10 @@ pub mod pallet {
 			//   - `Some((account, Err(())))`: error occured when sending the transaction
 			if let Some((_, res)) = signer.send_unsigned_transaction(
 				|acct| Payload { number, public: acct.public.clone() },
-				Call::submit_number_unsigned_with_signed_payload,
+				|payload, signature| Call::submit_number_unsigned_with_signed_payload {
+					payload,
+					signature,
+				},
 			) {
 				return res.map_err(|_| {
 					log::error!("Failed in offchain_unsigned_tx_signed_payload");
@@ -691,6 +1075,29 @@ pub mod pallet {
 		}
 	}
 
+	impl<T: Config> PriceProvider<T::BlockNumber> for Pallet<T> {
+		fn latest_price() -> Option<Price> {
+			Self::prices().back().map(|finalized| finalized.price)
+		}
+
+		fn price_at(block_number: T::BlockNumber) -> Option<Price> {
+			Self::prices()
+				.iter()
+				.rev()
+				.find(|finalized| finalized.block_number <= block_number)
+				.map(|finalized| finalized.price)
+		}
+
+		fn twap(window: T::BlockNumber) -> Option<Price> {
+			let history: Vec<_> = Self::prices()
+				.iter()
+				.map(|finalized| (finalized.block_number.saturated_into::<u128>(), finalized.price))
+				.collect();
+			let now = <frame_system::Pallet<T>>::block_number().saturated_into::<u128>();
+			price::twap(&history, now, window.saturated_into::<u128>())
+		}
+	}
+
 	impl<T: Config> BlockNumberProvider for Pallet<T> {
 		type BlockNumber = T::BlockNumber;
 
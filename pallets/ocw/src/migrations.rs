@@ -0,0 +1,100 @@
+//! 存储迁移
+
+use crate::{Config, Pallet};
+use frame_support::{
+	traits::{Get, GetStorageVersion, StorageVersion},
+	weights::Weight,
+};
+
+/// 价格由 `(u64, Permill)` 改为 `FinalizedPrice`，迁移 `Prices`
+pub mod v1 {
+	use super::*;
+	use crate::{CurrentRound, FinalizedPrice, Price, Prices, RoundIndex};
+	use sp_arithmetic::{per_things::Permill, FixedPointNumber};
+	use sp_runtime::traits::Zero;
+	use sp_std::collections::vec_deque::VecDeque;
+
+	/// 旧价格的小数部分精度为百万分之一，转换没有精度损失
+	fn to_price((integer, fraction): (u64, Permill)) -> Price {
+		Price::from_inner(
+			integer as u128 * Price::DIV +
+				fraction.deconstruct() as u128 * (Price::DIV / 1_000_000),
+		)
+	}
+
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() >= 1 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		// 旧价格没有轮次和区块：按顺序编号，区块记为 0
+		let translated = Prices::<T>::translate::<VecDeque<(u64, Permill)>, _>(|old| {
+			old.map(|prices| {
+				prices
+					.into_iter()
+					.enumerate()
+					.map(|(round, price)| FinalizedPrice {
+						round: round as RoundIndex,
+						block_number: Zero::zero(),
+						price: to_price(price),
+					})
+					.collect()
+			})
+		});
+		let migrated = match translated {
+			Ok(prices) => prices.map_or(0, |prices| prices.len()),
+			Err(_) => {
+				// 不更新存储版本，避免后续迁移在无法解码的数据上继续执行
+				log::error!("Prices 迁移失败，无法解码旧的价格，存储版本保持不变");
+				return T::DbWeight::get().reads(2)
+			},
+		};
+		// 新的轮次从已有价格之后开始，避免淘汰旧价格时清除进行中轮次的提交
+		CurrentRound::<T>::put(migrated as RoundIndex);
+
+		StorageVersion::new(1).put::<Pallet<T>>();
+		log::info!("pallet-ocw 迁移到 v1，共迁移 {} 个价格", migrated);
+		T::DbWeight::get().reads_writes(2, 3)
+	}
+}
+
+/// 增加 `PriceStats`，按现有的 `Prices` 初始化
+pub mod v2 {
+	use super::*;
+	use crate::{PriceStats, Prices, RunningStats};
+
+	pub fn migrate<T: Config>() -> Weight {
+		// 只在 v1 上执行，v1 迁移失败时不会跳过它
+		if Pallet::<T>::on_chain_storage_version() != 1 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		let mut stats = RunningStats::default();
+		Prices::<T>::get().iter().for_each(|finalized| stats.insert(finalized.price));
+		log::info!("pallet-ocw 迁移到 v2，价格统计: {:?}", stats);
+		PriceStats::<T>::put(stats);
+
+		StorageVersion::new(2).put::<Pallet<T>>();
+		T::DbWeight::get().reads_writes(2, 2)
+	}
+}
+
+/// 增加 `TaskSchedules`，写入与之前轮流执行相同的默认计划
+pub mod v3 {
+	use super::*;
+	use crate::{OffchainTask, TaskSchedules};
+
+	pub fn migrate<T: Config>() -> Weight {
+		if Pallet::<T>::on_chain_storage_version() != 2 {
+			return T::DbWeight::get().reads(1)
+		}
+
+		for task in OffchainTask::ALL {
+			TaskSchedules::<T>::insert(task, task.default_schedule::<T::BlockNumber>());
+		}
+
+		StorageVersion::new(3).put::<Pallet<T>>();
+		log::info!("pallet-ocw 迁移到 v3，写入默认任务计划");
+		T::DbWeight::get().reads_writes(1, OffchainTask::ALL.len() as u64 + 1)
+	}
+}
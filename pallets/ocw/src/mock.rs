@@ -0,0 +1,245 @@
+use crate::{self as pallet_ocw, PriceSource};
+use frame_support::parameter_types;
+use frame_system as system;
+use sp_core::{
+	offchain::{
+		testing::{OffchainState, PoolState, TestOffchainExt, TestTransactionPoolExt},
+		OffchainDbExt, OffchainWorkerExt, TransactionPoolExt,
+	},
+	sr25519::Signature,
+	H256,
+};
+use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
+use sp_runtime::{
+	testing::{Header, TestXt},
+	traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentifyAccount, IdentityLookup, Verify},
+	Permill, RuntimeAppPublic,
+};
+use std::sync::Arc;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		OcwModule: pallet_ocw::{Pallet, Call, Storage, Config<T>, Event<T>, ValidateUnsigned},
+		PriceConsumer: pallet_price_consumer::{Pallet, Call, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+
+// 与 runtime 一样使用 sr25519 公钥作为账户
+pub type AccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
+
+impl system::Config for Test {
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = SS58Prefix;
+	type OnSetCode = ();
+	type BaseCallFilter = frame_support::traits::Everything;
+	type MaxConsumers = frame_support::traits::ConstU32<16>;
+}
+
+pub type Extrinsic = TestXt<Call, ()>;
+
+impl frame_system::offchain::SigningTypes for Test {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<LocalCall> frame_system::offchain::SendTransactionTypes<LocalCall> for Test
+where
+	Call: From<LocalCall>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = Extrinsic;
+}
+
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Test
+where
+	Call: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: Call,
+		_public: <Signature as Verify>::Signer,
+		_account: AccountId,
+		nonce: u64,
+	) -> Option<(Call, <Extrinsic as ExtrinsicT>::SignaturePayload)> {
+		Some((call, (nonce, ())))
+	}
+}
+
+// 测试用的价格源，响应格式各不相同
+pub const TEST_PRICE_SOURCES: &[PriceSource] = &[
+	PriceSource { name: "alpha", url: "https://alpha.test/dot", json_path: &["data", "priceUsd"] },
+	PriceSource { name: "beta", url: "https://beta.test/dot", json_path: &["price"] },
+	PriceSource {
+		name: "gamma",
+		url: "https://gamma.test/dot",
+		json_path: &["result", "DOTUSD", "c", "0"],
+	},
+];
+
+parameter_types! {
+	pub const PriceSources: &'static [PriceSource] = TEST_PRICE_SOURCES;
+	pub const MaxPriceDeviation: Permill = Permill::from_percent(10);
+	// 测试中可以调低，以验证法定人数不低于预言机集合的过半数
+	pub static PriceQuorum: u32 = 3;
+	pub const MaxOracleAuthorities: u32 = 5;
+	pub const MaxPriceHistory: u32 = 10;
+	pub const RoundTimeout: u64 = 10;
+	pub TwapWindows: Vec<u64> = vec![4, 10];
+}
+
+impl pallet_ocw::Config for Test {
+	type Event = Event;
+	type Call = Call;
+	type AuthorityId = pallet_ocw::crypto::TestAuthId;
+	type PriceSources = PriceSources;
+	type MaxPriceDeviation = MaxPriceDeviation;
+	type MaxPriceHistory = MaxPriceHistory;
+	type TwapWindows = TwapWindows;
+	type PriceQuorum = PriceQuorum;
+	type ScheduleOrigin = frame_system::EnsureRoot<AccountId>;
+	type OracleOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxOracleAuthorities = MaxOracleAuthorities;
+	type RoundTimeout = RoundTimeout;
+}
+
+/// 示例：通过 `PriceProvider` 读取预言机价格的消费者 pallet
+#[frame_support::pallet]
+pub mod pallet_price_consumer {
+	use crate::{Price, PriceProvider};
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::{traits::Saturating, FixedPointNumber};
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+		type PriceProvider: PriceProvider<Self::BlockNumber>;
+		/// 报价使用的时间加权平均窗口
+		#[pallet::constant]
+		type TwapWindow: Get<Self::BlockNumber>;
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// 按时间加权平均价格报价 [who, dot_amount, usd_value]
+		Quoted(T::AccountId, u64, Price),
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		PriceUnavailable,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		#[pallet::weight(10_000)]
+		pub fn quote(origin: OriginFor<T>, dot_amount: u64) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			let price =
+				T::PriceProvider::twap(T::TwapWindow::get()).ok_or(Error::<T>::PriceUnavailable)?;
+			let usd_value = price.saturating_mul(Price::saturating_from_integer(dot_amount));
+			Self::deposit_event(Event::Quoted(who, dot_amount, usd_value));
+			Ok(())
+		}
+	}
+}
+
+impl pallet_price_consumer::Config for Test {
+	type Event = Event;
+	type PriceProvider = OcwModule;
+	type TwapWindow = frame_support::traits::ConstU64<10>;
+}
+
+#[macro_export]
+macro_rules! assert_has_event {
+	($x:expr) => {
+		System::assert_has_event(TestEvent::OcwModule($x))
+	};
+}
+
+// 测试用的预言机账户
+pub fn oracle(seed: u8) -> AccountId {
+	sp_core::sr25519::Public::from_raw([seed; 32])
+}
+
+// 创世时预言机集合中有 `oracle(1)` 到 `oracle(3)`
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut storage = system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+	pallet_ocw::GenesisConfig::<Test> {
+		oracle_authorities: (1..=3).map(oracle).collect(),
+		..Default::default()
+	}
+	.assimilate_storage(&mut storage)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(storage);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+/// 带有链下工作机环境的测试外部环境：模拟 HTTP、交易池，以及存有 Alice `demo` 密钥的 keystore，
+/// Alice 同时被加入预言机集合
+pub fn new_offchain_test_ext() -> (
+	sp_io::TestExternalities,
+	Arc<parking_lot::RwLock<OffchainState>>,
+	Arc<parking_lot::RwLock<PoolState>>,
+	sp_core::sr25519::Public,
+) {
+	let (offchain, offchain_state) = TestOffchainExt::new();
+	let (pool, pool_state) = TestTransactionPoolExt::new();
+	let keystore = KeyStore::new();
+	let public = SyncCryptoStore::sr25519_generate_new(
+		&keystore,
+		pallet_ocw::crypto::Public::ID,
+		Some("//Alice"),
+	)
+	.unwrap();
+
+	let mut ext = new_test_ext();
+	ext.register_extension(OffchainDbExt::new(offchain.clone()));
+	ext.register_extension(OffchainWorkerExt::new(offchain));
+	ext.register_extension(TransactionPoolExt::new(pool));
+	ext.register_extension(KeystoreExt(Arc::new(keystore)));
+	ext.execute_with(|| {
+		pallet_ocw::OracleAuthorities::<Test>::mutate(|authorities| {
+			authorities.try_push(public).unwrap()
+		})
+	});
+	(ext, offchain_state, pool_state, public)
+}
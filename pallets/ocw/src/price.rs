@@ -0,0 +1,316 @@
+//! DOT 价格源及聚合
+//!
+//! 链下工作机依次请求 `Config::PriceSources` 中的每个价格源，按各自的 JSON 路径取出价格，
+//! 剔除偏离中位数过多的离群值后，取剩余价格的中位数作为最终价格。
+//!
+//! 价格用 `FixedU128` 表示，精度为 18 位小数。
+//!
+//! 链上确定的价格通过 `PriceProvider` 提供给其他 pallet 使用，`RunningStats` 随价格历史增量更新，
+//! 用于统计最小值、最大值和标准差。
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use serde_json::Value;
+use sp_arithmetic::{per_things::Permill, traits::Saturating, FixedPointNumber, FixedU128};
+use sp_runtime::RuntimeDebug;
+use sp_std::prelude::*;
+
+/// DOT/USD 价格
+pub type Price = FixedU128;
+
+/// 其他 pallet 读取预言机价格的接口，可以作为 `Config` 的关联类型使用
+pub trait PriceProvider<BlockNumber> {
+	/// 最近确定的价格
+	fn latest_price() -> Option<Price>;
+	/// 指定区块时有效的价格，即该区块及之前最后确定的价格
+	fn price_at(block_number: BlockNumber) -> Option<Price>;
+	/// 最近 `window` 个区块的时间加权平均价格
+	fn twap(window: BlockNumber) -> Option<Price>;
+}
+
+/// 没有价格来源
+impl<BlockNumber> PriceProvider<BlockNumber> for () {
+	fn latest_price() -> Option<Price> {
+		None
+	}
+
+	fn price_at(_block_number: BlockNumber) -> Option<Price> {
+		None
+	}
+
+	fn twap(_window: BlockNumber) -> Option<Price> {
+		None
+	}
+}
+
+/// 价格字符串解析失败的原因
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum ParseError {
+	/// 没有任何数字
+	Empty,
+	/// 含有数字、小数点和指数以外的字符，或者有多个小数点
+	InvalidDigit,
+	/// 指数部分为空、不是整数或者超出范围
+	InvalidExponent,
+	/// 价格不能为负数
+	Negative,
+	/// 超出 `FixedU128` 的表示范围
+	Overflow,
+}
+
+/// 价格源：请求地址，以及从 JSON 响应中取出价格的路径
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub struct PriceSource {
+	/// 价格源名称，会随价格一起提交到链上
+	pub name: &'static str,
+	pub url: &'static str,
+	/// JSON 路径，对象按键名、数组按下标逐级取值，例如 `["data", "priceUsd"]`
+	pub json_path: &'static [&'static str],
+}
+
+/// 默认的 DOT/USD 价格源
+pub const DOT_PRICE_SOURCES: &[PriceSource] = &[
+	PriceSource {
+		name: "coincap",
+		url: "https://api.coincap.io/v2/assets/polkadot",
+		json_path: &["data", "priceUsd"],
+	},
+	PriceSource {
+		name: "binance",
+		url: "https://api.binance.com/api/v3/ticker/price?symbol=DOTUSDT",
+		json_path: &["price"],
+	},
+	PriceSource {
+		name: "kraken",
+		url: "https://api.kraken.com/0/public/Ticker?pair=DOTUSD",
+		json_path: &["result", "DOTUSD", "c", "0"],
+	},
+];
+
+/// 按 JSON 路径取出价格字符串，价格可以是 JSON 字符串或数字
+pub fn extract_price(body: &[u8], json_path: &[&str]) -> Option<Vec<u8>> {
+	let root: Value = serde_json::from_slice(body).ok()?;
+	let mut value = &root;
+	for key in json_path {
+		value = match value {
+			Value::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+			_ => value.get(*key)?,
+		};
+	}
+	match value {
+		Value::String(price) => Some(price.as_bytes().to_vec()),
+		Value::Number(_) => serde_json::to_vec(value).ok(),
+		_ => None,
+	}
+}
+
+/// 解析十进制价格，支持 `6.25`、`+6`、`.5`、`6.`、`625e-2` 这样的写法
+///
+/// 超过 18 位的小数四舍五入。
+pub fn parse_price(price_str: &str) -> Result<Price, ParseError> {
+	let price_str = price_str.trim();
+	let unsigned = match price_str.as_bytes().first() {
+		Some(b'-') => return Err(ParseError::Negative),
+		Some(b'+') => &price_str[1..],
+		_ => price_str,
+	};
+	let (mantissa, exponent) = match unsigned.find(|c: char| c == 'e' || c == 'E') {
+		Some(at) => {
+			let exponent = &unsigned[at + 1..];
+			// 指数只允许一位可选的符号加数字，避免 `e+-1` 之类的写法
+			let digits = exponent.strip_prefix(|c: char| c == '+' || c == '-').unwrap_or(exponent);
+			if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+				return Err(ParseError::InvalidExponent);
+			}
+			(&unsigned[..at], exponent.parse::<i32>().map_err(|_| ParseError::InvalidExponent)?)
+		},
+		None => (unsigned, 0),
+	};
+
+	let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+	if integer.is_empty() && fraction.is_empty() {
+		return Err(ParseError::Empty);
+	}
+	if !integer.bytes().chain(fraction.bytes()).all(|c| c.is_ascii_digit()) {
+		return Err(ParseError::InvalidDigit);
+	}
+
+	// 所有数字拼成整数后，小数点相对 18 位精度需要移动的位数
+	let digits: Vec<u8> = integer.bytes().chain(fraction.bytes()).map(|c| c - b'0').collect();
+	let digits = match digits.iter().position(|d| *d != 0) {
+		Some(first) => &digits[first..],
+		None => return Ok(Price::from_inner(0)),
+	};
+	let shift = exponent as i64 - fraction.len() as i64 + 18;
+
+	let (kept, round_up) = if shift >= 0 {
+		(digits, false)
+	} else {
+		// 舍去末尾的数字，按舍去的第一位四舍五入
+		let dropped = usize::try_from(shift.unsigned_abs()).unwrap_or(usize::MAX);
+		match digits.len().checked_sub(dropped) {
+			Some(kept_len) => (&digits[..kept_len], digits[kept_len] >= 5),
+			None => (&digits[..0], false),
+		}
+	};
+	let mut inner = kept
+		.iter()
+		.try_fold(0u128, |inner, d| inner.checked_mul(10)?.checked_add(*d as u128));
+	if shift > 0 {
+		let scale = u32::try_from(shift).ok().and_then(|shift| 10u128.checked_pow(shift));
+		inner = inner.zip(scale).and_then(|(inner, scale)| inner.checked_mul(scale));
+	}
+	inner
+		.and_then(|inner| inner.checked_add(round_up as u128))
+		.map(Price::from_inner)
+		.ok_or(ParseError::Overflow)
+}
+
+/// 中位数，个数为偶数时取中间两个数的平均值
+fn median(mut values: Vec<u128>) -> Option<u128> {
+	values.sort_unstable();
+	let mid = values.len() / 2;
+	match values.len() {
+		0 => None,
+		len if len % 2 == 1 => Some(values[mid]),
+		_ => {
+			// 分别折半后再相加，避免两个接近上限的值相加溢出
+			let (a, b) = (values[mid - 1], values[mid]);
+			Some(a / 2 + b / 2 + (a % 2 + b % 2) / 2)
+		},
+	}
+}
+
+/// 价格的中位数
+pub fn median_price(prices: &[Price]) -> Option<Price> {
+	median(prices.iter().map(|price| price.into_inner()).collect()).map(Price::from_inner)
+}
+
+/// 聚合各价格源的价格
+///
+/// 偏离全部价格中位数超过 `max_deviation` 的价格视为离群值，返回剩余价格的中位数，
+/// 以及每个价格是否为离群值。所有价格都是离群值时返回 `None`。
+pub fn aggregate(prices: &[Price], max_deviation: Permill) -> Option<(Price, Vec<bool>)> {
+	let inners: Vec<u128> = prices.iter().map(|price| price.into_inner()).collect();
+	let median_inner = median(inners.clone())?;
+	let tolerance = max_deviation.mul_floor(median_inner);
+
+	let outliers: Vec<bool> =
+		inners.iter().map(|price| price.abs_diff(median_inner) > tolerance).collect();
+	let accepted = inners
+		.iter()
+		.zip(&outliers)
+		.filter(|(_, outlier)| !**outlier)
+		.map(|(price, _)| *price)
+		.collect();
+	median(accepted).map(|price| (Price::from_inner(price), outliers))
+}
+
+/// 时间加权平均价格
+///
+/// `history` 为按区块升序排列的 `(区块, 价格)`，每个价格一直有效到下一个价格确定为止。
+/// 只统计 `[now - window, now)` 内有价格的区块，没有可统计的区块时取 `now` 时有效的价格。
+pub fn twap(history: &[(u128, Price)], now: u128, window: u128) -> Option<Price> {
+	let start = now.saturating_sub(window);
+	let (mut weighted, mut blocks) = (0u128, 0u128);
+	for (i, (block_number, price)) in history.iter().enumerate() {
+		let from = (*block_number).max(start);
+		let until = history.get(i + 1).map_or(now, |(next, _)| (*next).min(now));
+		if until > from {
+			weighted = weighted.saturating_add(price.into_inner().saturating_mul(until - from));
+			blocks += until - from;
+		}
+	}
+
+	if blocks == 0 {
+		return history
+			.iter()
+			.rev()
+			.find(|(block_number, _)| *block_number <= now)
+			.map(|(_, price)| *price);
+	}
+	Some(Price::from_inner(weighted / blocks))
+}
+
+/// 价格历史的统计量，加入和淘汰价格时增量更新
+#[derive(Encode, Decode, Clone, Default, PartialEq, Eq, RuntimeDebug, TypeInfo)]
+pub struct RunningStats {
+	/// 统计的价格个数
+	pub count: u32,
+	pub sum: Price,
+	/// 价格平方和，用于计算方差
+	pub sum_of_squares: Price,
+	pub min: Price,
+	pub max: Price,
+}
+
+impl RunningStats {
+	/// 加入一个价格
+	pub fn insert(&mut self, price: Price) {
+		if self.count == 0 {
+			self.min = price;
+			self.max = price;
+		} else {
+			self.min = self.min.min(price);
+			self.max = self.max.max(price);
+		}
+		self.count += 1;
+		self.sum = self.sum.saturating_add(price);
+		self.sum_of_squares = self.sum_of_squares.saturating_add(price.saturating_mul(price));
+	}
+
+	/// 淘汰一个价格，`remaining` 为淘汰后剩余的价格
+	///
+	/// 只有淘汰的是最小值或最大值时才需要遍历剩余价格。
+	pub fn remove(&mut self, price: Price, remaining: impl Iterator<Item = Price> + Clone) {
+		self.count = self.count.saturating_sub(1);
+		if self.count == 0 {
+			*self = Self::default();
+			return
+		}
+		self.sum = self.sum.saturating_sub(price);
+		self.sum_of_squares = self.sum_of_squares.saturating_sub(price.saturating_mul(price));
+		if price == self.min {
+			self.min = remaining.clone().min().unwrap_or_default();
+		}
+		if price == self.max {
+			self.max = remaining.max().unwrap_or_default();
+		}
+	}
+
+	pub fn mean(&self) -> Option<Price> {
+		(self.count > 0).then(|| Price::from_inner(self.sum.into_inner() / self.count as u128))
+	}
+
+	/// 总体标准差
+	pub fn std_dev(&self) -> Option<Price> {
+		let mean = self.mean()?;
+		let mean_of_squares =
+			Price::from_inner(self.sum_of_squares.into_inner() / self.count as u128);
+		Some(sqrt(mean_of_squares.saturating_sub(mean.saturating_mul(mean))))
+	}
+}
+
+/// 定点数的平方根，结果向下取整
+pub fn sqrt(value: Price) -> Price {
+	// sqrt(inner / DIV) * DIV = sqrt(inner * DIV)，乘法溢出时降低精度
+	match value.into_inner().checked_mul(Price::DIV) {
+		Some(scaled) => Price::from_inner(integer_sqrt(scaled)),
+		None => Price::from_inner(integer_sqrt(value.into_inner()).saturating_mul(1_000_000_000)),
+	}
+}
+
+fn integer_sqrt(n: u128) -> u128 {
+	if n < 2 {
+		return n
+	}
+	// 牛顿迭代，从不小于平方根的值开始单调递减
+	let mut x = 1u128 << ((128 - n.leading_zeros() + 1) / 2);
+	loop {
+		let next = (x + n / x) / 2;
+		if next >= x {
+			return x
+		}
+		x = next;
+	}
+}
@@ -0,0 +1,1028 @@
+use super::*;
+use crate::mock::{
+	new_offchain_test_ext, new_test_ext, oracle, pallet_price_consumer, Event as TestEvent,
+	Extrinsic, MaxPriceHistory, OcwModule, Origin, PriceConsumer, PriceQuorum, RoundTimeout, System,
+	Test, TEST_PRICE_SOURCES,
+};
+use codec::Decode;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{GetStorageVersion, Hooks, OffchainWorker, OnRuntimeUpgrade, StorageVersion},
+	unsigned::{TransactionSource, ValidateUnsigned},
+};
+use http_client::{Breaker, FetchError};
+use price::ParseError;
+use sp_arithmetic::{per_things::Permill, FixedPointNumber};
+use sp_core::{
+	offchain::{
+		testing::{OffchainState, PendingRequest, PoolState},
+		Duration, Timestamp,
+	},
+	sr25519,
+};
+use sp_runtime::{
+	offchain::{
+		storage::StorageValueRef,
+		storage_lock::{BlockAndTime, StorageLock},
+	},
+	transaction_validity::InvalidTransaction,
+	DispatchResult,
+};
+
+// 模拟单个价格源的响应
+fn expect_source_request(state: &mut OffchainState, url: &str, body: String) {
+	state.expect_request(PendingRequest {
+		method: "GET".into(),
+		uri: url.into(),
+		response: Some(body.into_bytes()),
+		sent: true,
+		..Default::default()
+	});
+}
+
+// 按各测试价格源的响应格式模拟三个价格
+fn expect_price_requests(state: &mut OffchainState, [alpha, beta, gamma]: [&str; 3]) {
+	expect_source_request(
+		state,
+		TEST_PRICE_SOURCES[0].url,
+		format!(r#"{{"data":{{"id":"polkadot","priceUsd":"{}"}},"timestamp":1}}"#, alpha),
+	);
+	expect_source_request(
+		state,
+		TEST_PRICE_SOURCES[1].url,
+		format!(r#"{{"symbol":"DOTUSDT","price":"{}"}}"#, beta),
+	);
+	expect_source_request(
+		state,
+		TEST_PRICE_SOURCES[2].url,
+		format!(r#"{{"error":[],"result":{{"DOTUSD":{{"c":["{}","1.0"]}}}}}}"#, gamma),
+	);
+}
+
+// 整数部分加上百万分之一为单位的小数部分
+fn price(integer: u64, parts: u32) -> Price {
+	Price::saturating_from_integer(integer) + Price::saturating_from_rational(parts, 1_000_000)
+}
+
+// 取出交易池中唯一的价格交易
+fn pop_price_call(
+	pool_state: &parking_lot::RwLock<PoolState>,
+) -> (PricePayload<sr25519::Public, u64>, sr25519::Signature) {
+	let tx = pool_state.write().transactions.pop().unwrap();
+	assert!(pool_state.read().transactions.is_empty());
+	let tx = Extrinsic::decode(&mut &*tx).unwrap();
+	assert_eq!(tx.signature, None);
+	match tx.call {
+		crate::mock::Call::OcwModule(Call::submit_price_unsigned_with_signed_payload {
+			price_payload,
+			signature,
+		}) => (price_payload, signature),
+		call => panic!("Unexpected call: {:?}", call),
+	}
+}
+
+// 以指定预言机的身份提交价格，签名已在 `validate_unsigned` 中校验过
+fn submit_price(oracle: sr25519::Public, round: RoundIndex, price: Price) -> DispatchResult {
+	OcwModule::submit_price_unsigned_with_signed_payload(
+		Origin::none(),
+		PricePayload {
+			block_number: 1,
+			round,
+			parsed_price: price,
+			sources: vec![],
+			public: oracle,
+		},
+		sr25519::Signature::from_raw([0u8; 64]),
+	)
+}
+
+// 三个预言机提交价格，凑齐法定人数
+fn finalize_round(round: RoundIndex, price: Price) {
+	for seed in 1..=3 {
+		assert_ok!(submit_price(oracle(seed), round, price));
+	}
+}
+
+/**************************Parse_price_test*******************/
+#[test]
+fn parse_price_works() {
+	assert_eq!(price::parse_price("6.123456"), Ok(price(6, 123_456)));
+	assert_eq!(price::parse_price("7"), Ok(price(7, 0)));
+	// 不再限制为六位小数
+	assert_eq!(price::parse_price("6.1"), Ok(price(6, 100_000)));
+	assert_eq!(
+		price::parse_price("6.2345678901"),
+		Ok(Price::from_inner(6_234_567_890_100_000_000))
+	);
+	// 省略整数或小数部分，带正号，以及前后空白
+	assert_eq!(price::parse_price(".5"), Ok(price(0, 500_000)));
+	assert_eq!(price::parse_price("6."), Ok(price(6, 0)));
+	assert_eq!(price::parse_price("+6.5"), Ok(price(6, 500_000)));
+	assert_eq!(price::parse_price(" 6.5\n"), Ok(price(6, 500_000)));
+	assert_eq!(price::parse_price("0.000"), Ok(Price::from_inner(0)));
+}
+
+#[test]
+fn parse_price_supports_exponent_notation() {
+	assert_eq!(price::parse_price("625e-2"), Ok(price(6, 250_000)));
+	assert_eq!(price::parse_price("6.25E+1"), Ok(price(62, 500_000)));
+	assert_eq!(price::parse_price("1.5e3"), Ok(price(1_500, 0)));
+	assert_eq!(price::parse_price("5e-18"), Ok(Price::from_inner(5)));
+}
+
+#[test]
+fn parse_price_rounds_to_precision() {
+	// 超过 18 位的小数四舍五入
+	assert_eq!(price::parse_price("0.0000000000000000014"), Ok(Price::from_inner(1)));
+	assert_eq!(price::parse_price("0.0000000000000000015"), Ok(Price::from_inner(2)));
+	assert_eq!(price::parse_price("1.9999999999999999999"), Ok(price(2, 0)));
+	assert_eq!(price::parse_price("5e-30"), Ok(Price::from_inner(0)));
+}
+
+#[test]
+fn parse_price_rejects_invalid_input() {
+	assert_eq!(price::parse_price(""), Err(ParseError::Empty));
+	assert_eq!(price::parse_price("."), Err(ParseError::Empty));
+	assert_eq!(price::parse_price("e5"), Err(ParseError::Empty));
+	assert_eq!(price::parse_price("abc"), Err(ParseError::InvalidDigit));
+	assert_eq!(price::parse_price("6.1a"), Err(ParseError::InvalidDigit));
+	assert_eq!(price::parse_price("6.1.2"), Err(ParseError::InvalidDigit));
+	assert_eq!(price::parse_price("1e"), Err(ParseError::InvalidExponent));
+	assert_eq!(price::parse_price("1e+-1"), Err(ParseError::InvalidExponent));
+	assert_eq!(price::parse_price("1e99999999999"), Err(ParseError::InvalidExponent));
+	assert_eq!(price::parse_price("-6.5"), Err(ParseError::Negative));
+	assert_eq!(price::parse_price("1e40"), Err(ParseError::Overflow));
+	assert_eq!(price::parse_price("340282366920938463464"), Err(ParseError::Overflow));
+}
+
+/**************************Price_source_test*******************/
+#[test]
+fn extract_price_follows_json_path() {
+	let body = br#"{"data":{"priceUsd":"6.5"},"result":{"DOTUSD":{"c":["7.25","1"]}},"n":8.5}"#;
+	assert_eq!(price::extract_price(body, &["data", "priceUsd"]), Some(b"6.5".to_vec()));
+	assert_eq!(price::extract_price(body, &["result", "DOTUSD", "c", "0"]), Some(b"7.25".to_vec()));
+	// 数字类型的价格
+	assert_eq!(price::extract_price(body, &["n"]), Some(b"8.5".to_vec()));
+	// 路径不存在、类型不对或者 JSON 非法
+	assert_eq!(price::extract_price(body, &["data", "price"]), None);
+	assert_eq!(price::extract_price(body, &["result", "DOTUSD", "c", "x"]), None);
+	assert_eq!(price::extract_price(body, &["data"]), None);
+	assert_eq!(price::extract_price(b"not json", &["data"]), None);
+}
+
+#[test]
+fn aggregate_drops_outliers_and_takes_median() {
+	let deviation = Permill::from_percent(10);
+	assert_eq!(price::aggregate(&[], deviation), None);
+	assert_eq!(price::aggregate(&[price(6, 0)], deviation), Some((price(6, 0), vec![false])));
+	// 偶数个价格取中间两个的平均值
+	assert_eq!(
+		price::aggregate(&[price(6, 0), price(6, 500_000)], deviation),
+		Some((price(6, 250_000), vec![false, false]))
+	);
+	// 偏离中位数超过 10% 的价格被剔除
+	assert_eq!(
+		price::aggregate(
+			&[price(6, 0), price(60, 0), price(6, 200_000), price(5, 900_000)],
+			deviation
+		),
+		Some((price(6, 0), vec![false, true, false, false]))
+	);
+	// 价格差距过大时全部是离群值
+	assert_eq!(price::aggregate(&[price(1, 0), price(9, 0)], deviation), None);
+}
+
+#[test]
+fn median_price_does_not_overflow_near_max() {
+	let max = Price::from_inner(u128::MAX);
+	let below_max = Price::from_inner(u128::MAX - 1);
+	assert_eq!(price::median_price(&[max, max]), Some(max));
+	assert_eq!(price::median_price(&[max, below_max]), Some(below_max));
+	assert_eq!(
+		price::median_price(&[price(1, 0), max]),
+		Some(Price::from_inner(u128::MAX / 2 + Price::DIV / 2))
+	);
+}
+
+/**************************Price_feed_test*******************/
+#[test]
+fn offchain_worker_submits_median_price_with_source_details() {
+	let (mut ext, offchain_state, pool_state, public) = new_offchain_test_ext();
+	expect_price_requests(&mut offchain_state.write(), ["6.2345678901", "6.3", "6.25"]);
+
+	ext.execute_with(|| {
+		// 第 4 个区块执行价格获取任务
+		OcwModule::offchain_worker(4);
+		let (price_payload, signature) = pop_price_call(&pool_state);
+		let sources = vec![
+			SourcePrice {
+				source: b"alpha".to_vec(),
+				price: Price::from_inner(6_234_567_890_100_000_000),
+				outlier: false,
+			},
+			SourcePrice { source: b"beta".to_vec(), price: price(6, 300_000), outlier: false },
+			SourcePrice { source: b"gamma".to_vec(), price: price(6, 250_000), outlier: false },
+		];
+		assert_eq!(
+			price_payload,
+			PricePayload {
+				block_number: 4,
+				round: 0,
+				parsed_price: price(6, 250_000),
+				sources: sources.clone(),
+				public,
+			}
+		);
+
+		let call = Call::submit_price_unsigned_with_signed_payload {
+			price_payload: price_payload.clone(),
+			signature: signature.clone(),
+		};
+		assert!(OcwModule::validate_unsigned(TransactionSource::Local, &call).is_ok());
+		assert_ok!(OcwModule::submit_price_unsigned_with_signed_payload(
+			Origin::none(),
+			price_payload,
+			signature
+		));
+		// 只有一个预言机提交，尚未达到法定人数
+		assert_eq!(
+			OcwModule::round_submissions(0, public),
+			Some(PriceSubmission { price: price(6, 250_000), sources })
+		);
+		assert!(OcwModule::prices().is_empty());
+		assert_has_event!(Event::<Test>::PriceSubmitted(public, 0, price(6, 250_000)));
+	});
+}
+
+#[test]
+fn offchain_worker_drops_outlier_source() {
+	let (mut ext, offchain_state, pool_state, _) = new_offchain_test_ext();
+	expect_price_requests(&mut offchain_state.write(), ["6.2", "62", "6.4"]);
+
+	ext.execute_with(|| {
+		OcwModule::offchain_worker(4);
+		let (price_payload, _) = pop_price_call(&pool_state);
+		assert_eq!(price_payload.parsed_price, price(6, 300_000));
+		let outliers: Vec<_> = price_payload.sources.iter().map(|s| s.outlier).collect();
+		assert_eq!(outliers, vec![false, true, false]);
+	});
+}
+
+#[test]
+fn offchain_worker_ignores_failed_source() {
+	let (mut ext, offchain_state, pool_state, _) = new_offchain_test_ext();
+	expect_price_requests(&mut offchain_state.write(), ["6.5", "n/a", "6.7"]);
+
+	ext.execute_with(|| {
+		OcwModule::offchain_worker(4);
+		let (price_payload, _) = pop_price_call(&pool_state);
+		assert_eq!(price_payload.parsed_price, price(6, 600_000));
+		let sources: Vec<_> = price_payload.sources.iter().map(|s| s.source.clone()).collect();
+		assert_eq!(sources, vec![b"alpha".to_vec(), b"gamma".to_vec()]);
+	});
+}
+
+#[test]
+fn offchain_worker_fetches_price_every_time() {
+	let (mut ext, offchain_state, pool_state, _) = new_offchain_test_ext();
+	expect_price_requests(&mut offchain_state.write(), ["6.5", "6.5", "6.5"]);
+	expect_price_requests(&mut offchain_state.write(), ["7.25", "7.25", "7.25"]);
+
+	ext.execute_with(|| {
+		OcwModule::offchain_worker(4);
+		// 锁在请求结束后释放，下一轮会重新请求价格
+		OcwModule::offchain_worker(9);
+		assert_eq!(pool_state.read().transactions.len(), 2);
+	});
+}
+
+#[test]
+fn offchain_worker_skips_when_no_valid_price() {
+	let (mut ext, offchain_state, pool_state, _) = new_offchain_test_ext();
+	expect_price_requests(&mut offchain_state.write(), ["n/a", "", "abc"]);
+
+	ext.execute_with(|| {
+		OcwModule::offchain_worker(4);
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn unsigned_price_with_bad_signature_is_rejected() {
+	let (mut ext, offchain_state, pool_state, public) = new_offchain_test_ext();
+	expect_price_requests(&mut offchain_state.write(), ["6.5", "6.5", "6.5"]);
+
+	ext.execute_with(|| {
+		OcwModule::offchain_worker(4);
+		let (price_payload, signature) = pop_price_call(&pool_state);
+		// 篡改价格后签名不再匹配
+		let call = Call::submit_price_unsigned_with_signed_payload {
+			price_payload: PricePayload { parsed_price: price(600, 0), public, ..price_payload },
+			signature,
+		};
+		assert_eq!(
+			OcwModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadProof.into()
+		);
+	});
+}
+
+/**************************Endpoint_test*******************/
+// 在链下持久化存储中为地址名称写入配置，与节点命令行参数和 RPC 的效果相同
+fn configure_endpoint(name: &str, url: &str, headers: &[(&str, &str)]) {
+	let headers = headers.iter().map(|(h, v)| (h.as_bytes().to_vec(), v.as_bytes().to_vec()));
+	StorageValueRef::persistent(&endpoint::storage_key(name.as_bytes()))
+		.set(&Endpoint { url: url.as_bytes().to_vec(), headers: headers.collect() });
+}
+
+#[test]
+fn endpoint_falls_back_to_default_url() {
+	let (mut ext, _, _, _) = new_offchain_test_ext();
+	ext.execute_with(|| {
+		let default = Endpoint::load("github", "https://api.github.com/orgs/x")
+			.with_default_header("User-Agent", "ocw");
+		assert_eq!(default.url, b"https://api.github.com/orgs/x".to_vec());
+		assert_eq!(default.headers, vec![(b"User-Agent".to_vec(), b"ocw".to_vec())]);
+
+		// 节点配置的同名请求头优先
+		configure_endpoint("github", "http://localhost:8080/org", &[("user-agent", "node")]);
+		let configured = Endpoint::load("github", "https://api.github.com/orgs/x")
+			.with_default_header("User-Agent", "ocw");
+		assert_eq!(configured.url, b"http://localhost:8080/org".to_vec());
+		assert_eq!(configured.headers, vec![(b"user-agent".to_vec(), b"node".to_vec())]);
+	});
+}
+
+#[test]
+fn offchain_worker_uses_configured_endpoints() {
+	let (mut ext, offchain_state, pool_state, _) = new_offchain_test_ext();
+	{
+		let mut state = offchain_state.write();
+		expect_source_request(
+			&mut state,
+			TEST_PRICE_SOURCES[0].url,
+			r#"{"data":{"priceUsd":"6.5"}}"#.into(),
+		);
+		// beta 改用本地的替代服务，并带上 API key
+		state.expect_request(PendingRequest {
+			method: "GET".into(),
+			uri: "http://localhost:8080/dot".into(),
+			headers: vec![("X-Api-Key".into(), "secret".into())],
+			response: Some(br#"{"price":"6.6"}"#.to_vec()),
+			sent: true,
+			..Default::default()
+		});
+		// gamma 只配置请求头，仍使用默认地址
+		state.expect_request(PendingRequest {
+			method: "GET".into(),
+			uri: TEST_PRICE_SOURCES[2].url.into(),
+			headers: vec![("Authorization".into(), "Bearer token".into())],
+			response: Some(br#"{"result":{"DOTUSD":{"c":["6.7"]}}}"#.to_vec()),
+			sent: true,
+			..Default::default()
+		});
+	}
+
+	ext.execute_with(|| {
+		configure_endpoint("beta", "http://localhost:8080/dot", &[("X-Api-Key", "secret")]);
+		configure_endpoint("gamma", "", &[("Authorization", "Bearer token")]);
+
+		OcwModule::offchain_worker(4);
+		let (price_payload, _) = pop_price_call(&pool_state);
+		assert_eq!(price_payload.parsed_price, price(6, 600_000));
+	});
+}
+
+/**************************Http_client_test*******************/
+#[test]
+fn retry_backs_off_until_success() {
+	let (mut ext, _, _, _) = new_offchain_test_ext();
+	ext.execute_with(|| {
+		let start = sp_io::offchain::timestamp();
+		let deadline = start.add(Duration::from_millis(3000));
+		let mut attempts = vec![];
+		let result = http_client::retry(deadline, |_| {
+			attempts.push(sp_io::offchain::timestamp().diff(&start).millis());
+			if attempts.len() < 3 {
+				Err(FetchError::Http)
+			} else {
+				Ok(b"ok".to_vec())
+			}
+		});
+		assert_eq!(result, Ok(b"ok".to_vec()));
+		// 等待时间从 200 毫秒开始翻倍
+		assert_eq!(attempts, vec![0, 200, 600]);
+	});
+}
+
+#[test]
+fn retry_gives_up_at_deadline_and_on_client_errors() {
+	let (mut ext, _, _, _) = new_offchain_test_ext();
+	ext.execute_with(|| {
+		let count_attempts = |timeout: u64, error: FetchError| {
+			let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(timeout));
+			let mut attempts = 0;
+			let result = http_client::retry::<()>(deadline, |_| {
+				attempts += 1;
+				Err(error)
+			});
+			assert_eq!(result, Err(error));
+			attempts
+		};
+		assert_eq!(count_attempts(3000, FetchError::Status(503)), http_client::MAX_ATTEMPTS);
+		// 第三次请求要等到 600 毫秒之后，超过截止时间
+		assert_eq!(count_attempts(500, FetchError::Http), 2);
+		assert_eq!(count_attempts(3000, FetchError::Status(404)), 1);
+	});
+}
+
+#[test]
+fn breaker_opens_after_repeated_failures() {
+	let now = Timestamp::from_unix_millis(1_000);
+	let mut breaker = Breaker::default();
+	for _ in 1..http_client::FAILURE_THRESHOLD {
+		breaker.record_failure("alpha", now);
+	}
+	assert!(!breaker.is_open(now));
+
+	breaker.record_failure("alpha", now);
+	assert!(breaker.is_open(now));
+	assert!(breaker.is_open(now.add(Duration::from_millis(http_client::COOL_DOWN - 1))));
+	// 冷却结束后放行请求
+	assert!(!breaker.is_open(now.add(Duration::from_millis(http_client::COOL_DOWN))));
+
+	breaker.record_success("alpha");
+	assert_eq!(breaker, Breaker::default());
+}
+
+#[test]
+fn offchain_worker_skips_source_with_open_breaker() {
+	let (mut ext, offchain_state, pool_state, _) = new_offchain_test_ext();
+	{
+		let mut state = offchain_state.write();
+		expect_source_request(
+			&mut state,
+			TEST_PRICE_SOURCES[0].url,
+			r#"{"data":{"priceUsd":"6.5"}}"#.into(),
+		);
+		expect_source_request(
+			&mut state,
+			TEST_PRICE_SOURCES[2].url,
+			r#"{"result":{"DOTUSD":{"c":["6.7"]}}}"#.into(),
+		);
+	}
+
+	ext.execute_with(|| {
+		let open_until = sp_io::offchain::timestamp().unix_millis() + http_client::COOL_DOWN;
+		let open =
+			Breaker { failures: http_client::FAILURE_THRESHOLD, open_until: Some(open_until) };
+		StorageValueRef::persistent(&http_client::breaker_key("beta")).set(&open);
+
+		// 熔断器打开期间不请求 beta
+		OcwModule::offchain_worker(4);
+		let (price_payload, _) = pop_price_call(&pool_state);
+		let sources: Vec<_> = price_payload.sources.iter().map(|s| s.source.clone()).collect();
+		assert_eq!(sources, vec![b"alpha".to_vec(), b"gamma".to_vec()]);
+		assert_eq!(http_client::breaker("beta"), open);
+
+		// 冷却结束后 beta 请求成功，熔断器关闭
+		offchain_state.write().timestamp = open_until;
+		expect_price_requests(&mut offchain_state.write(), ["6.5", "6.6", "6.7"]);
+		OcwModule::offchain_worker(9);
+		let (price_payload, _) = pop_price_call(&pool_state);
+		assert_eq!(price_payload.sources.len(), 3);
+		assert_eq!(http_client::breaker("beta"), Breaker::default());
+	});
+}
+
+/**************************Round_test*******************/
+#[test]
+fn round_finalizes_with_median_at_quorum() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(submit_price(oracle(1), 0, price(6, 0)));
+		assert_ok!(submit_price(oracle(2), 0, price(9, 0)));
+		assert!(OcwModule::prices().is_empty());
+		assert_eq!(OcwModule::current_round(), 0);
+
+		System::set_block_number(3);
+		assert_ok!(submit_price(oracle(3), 0, price(6, 500_000)));
+		assert_eq!(
+			OcwModule::prices(),
+			vec![FinalizedPrice { round: 0, block_number: 3, price: price(6, 500_000) }]
+		);
+		assert_eq!(OcwModule::current_round(), 1);
+		assert_has_event!(Event::<Test>::PriceFinalized(0, 3, price(6, 500_000)));
+		// 单个预言机无法把价格拉偏
+		assert_eq!(OcwModule::round_submissions(0, oracle(2)).unwrap().price, price(9, 0));
+	});
+}
+
+#[test]
+fn quorum_is_at_least_oracle_majority() {
+	new_test_ext().execute_with(|| {
+		PriceQuorum::set(1);
+		// 3 个预言机中需要 2 个提交
+		assert_eq!(OcwModule::price_quorum(), 2);
+		assert_ok!(submit_price(oracle(1), 0, price(6, 0)));
+		assert_eq!(OcwModule::current_round(), 0);
+		assert_ok!(submit_price(oracle(2), 0, price(8, 0)));
+		assert_eq!(OcwModule::current_round(), 1);
+
+		// 集合扩大后法定人数随之提高
+		assert_ok!(OcwModule::add_oracle_authority(Origin::root(), oracle(4)));
+		assert_ok!(OcwModule::add_oracle_authority(Origin::root(), oracle(5)));
+		assert_eq!(OcwModule::price_quorum(), 3);
+		assert_ok!(submit_price(oracle(1), 1, price(6, 0)));
+		assert_ok!(submit_price(oracle(2), 1, price(6, 0)));
+		assert_eq!(OcwModule::current_round(), 1);
+
+		// 配置的最少提交数更高时以配置为准
+		PriceQuorum::set(4);
+		assert_eq!(OcwModule::price_quorum(), 4);
+	});
+}
+
+#[test]
+fn round_expires_after_timeout() {
+	new_test_ext().execute_with(|| {
+		OcwModule::on_initialize(1);
+		assert_eq!(OcwModule::round_started_at(), Some(1));
+		assert_ok!(submit_price(oracle(1), 0, price(6, 0)));
+
+		let expire_at = 1 + RoundTimeout::get();
+		OcwModule::on_initialize(expire_at - 1);
+		assert_eq!(OcwModule::current_round(), 0);
+
+		System::set_block_number(expire_at);
+		OcwModule::on_initialize(expire_at);
+		assert_eq!(OcwModule::current_round(), 1);
+		assert_eq!(OcwModule::round_started_at(), Some(expire_at));
+		assert!(OcwModule::prices().is_empty());
+		// 作废轮次的提交一并清除，之后不再接受提交
+		assert_eq!(RoundSubmissions::<Test>::iter_prefix(0).count(), 0);
+		assert_has_event!(Event::<Test>::RoundExpired(0, expire_at));
+		assert_noop!(submit_price(oracle(2), 0, price(6, 0)), Error::<Test>::StaleRound);
+		assert_ok!(submit_price(oracle(2), 1, price(6, 0)));
+	});
+}
+
+#[test]
+fn timed_out_round_finalizes_when_quorum_drops() {
+	new_test_ext().execute_with(|| {
+		PriceQuorum::set(1);
+		OcwModule::on_initialize(1);
+		assert_ok!(OcwModule::add_oracle_authority(Origin::root(), oracle(4)));
+		assert_ok!(submit_price(oracle(1), 0, price(6, 0)));
+		assert_ok!(submit_price(oracle(2), 0, price(8, 0)));
+		assert_eq!(OcwModule::current_round(), 0);
+
+		// 移出预言机后 2 个提交已经足够，超时时确定价格而不是作废
+		assert_ok!(OcwModule::remove_oracle_authority(Origin::root(), oracle(4)));
+		let expire_at = 1 + RoundTimeout::get();
+		System::set_block_number(expire_at);
+		OcwModule::on_initialize(expire_at);
+		assert_eq!(OcwModule::current_round(), 1);
+		assert_eq!(OcwModule::prices().back().map(|p| p.round), Some(0));
+		assert_has_event!(Event::<Test>::PriceFinalized(0, expire_at, price(7, 0)));
+	});
+}
+
+#[test]
+fn stale_and_duplicate_submissions_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(submit_price(oracle(1), 0, price(6, 0)));
+		assert_noop!(submit_price(oracle(1), 0, price(7, 0)), Error::<Test>::DuplicateSubmission);
+		assert_noop!(submit_price(oracle(2), 1, price(6, 0)), Error::<Test>::StaleRound);
+
+		assert_ok!(submit_price(oracle(2), 0, price(6, 0)));
+		assert_ok!(submit_price(oracle(3), 0, price(6, 0)));
+		// 第 0 轮结束后不再接受提交
+		assert_noop!(submit_price(oracle(4), 0, price(6, 0)), Error::<Test>::StaleRound);
+	});
+}
+
+#[test]
+fn stale_unsigned_price_is_not_pooled() {
+	let (mut ext, offchain_state, pool_state, _) = new_offchain_test_ext();
+	expect_price_requests(&mut offchain_state.write(), ["6.5", "6.5", "6.5"]);
+
+	ext.execute_with(|| {
+		OcwModule::offchain_worker(4);
+		let (price_payload, signature) = pop_price_call(&pool_state);
+		let call = Call::submit_price_unsigned_with_signed_payload {
+			price_payload: price_payload.clone(),
+			signature: signature.clone(),
+		};
+		assert!(OcwModule::validate_unsigned(TransactionSource::External, &call).is_ok());
+
+		// 已经提交过的预言机不能再次提交
+		assert_ok!(OcwModule::submit_price_unsigned_with_signed_payload(
+			Origin::none(),
+			price_payload,
+			signature
+		));
+		assert_eq!(
+			OcwModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Stale.into()
+		);
+
+		// 轮次结束后签名有效的旧提交同样过期
+		assert_ok!(submit_price(oracle(1), 0, price(6, 0)));
+		assert_ok!(submit_price(oracle(2), 0, price(6, 0)));
+		assert_eq!(OcwModule::current_round(), 1);
+		assert_eq!(
+			OcwModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Stale.into()
+		);
+	});
+}
+
+#[test]
+fn prices_keep_latest_rounds_up_to_max_history() {
+	new_test_ext().execute_with(|| {
+		let max_history = MaxPriceHistory::get();
+		for round in 0..max_history {
+			finalize_round(round, price(1, 0));
+		}
+		finalize_round(max_history, price(2, 0));
+
+		let prices = OcwModule::prices();
+		assert_eq!(prices.len(), max_history as usize);
+		assert_eq!(prices.front().map(|p| p.round), Some(1));
+		assert_eq!(prices.back().map(|p| p.price), Some(price(2, 0)));
+		// 被淘汰轮次的提交一并清除
+		assert_eq!(RoundSubmissions::<Test>::iter_prefix(0).count(), 0);
+		assert_eq!(RoundSubmissions::<Test>::iter_prefix(1).count(), 3);
+		// 统计量只包含保留的价格
+		let mut stats = RunningStats::default();
+		prices.iter().for_each(|p| stats.insert(p.price));
+		assert_eq!(OcwModule::price_stats(), stats);
+	});
+}
+
+/**************************Oracle_authority_test*******************/
+#[test]
+fn root_manages_oracle_authorities() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(OcwModule::oracle_authorities().to_vec(), vec![oracle(1), oracle(2), oracle(3)]);
+
+		assert_noop!(
+			OcwModule::add_oracle_authority(Origin::signed(oracle(1)), oracle(4)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(OcwModule::add_oracle_authority(Origin::root(), oracle(4)));
+		assert!(OcwModule::is_oracle_authority(&oracle(4)));
+		assert_has_event!(Event::<Test>::OracleAuthorityAdded(oracle(4)));
+		assert_noop!(
+			OcwModule::add_oracle_authority(Origin::root(), oracle(4)),
+			Error::<Test>::AlreadyOracleAuthority
+		);
+
+		// 预言机数量上限为 5
+		assert_ok!(OcwModule::add_oracle_authority(Origin::root(), oracle(5)));
+		assert_noop!(
+			OcwModule::add_oracle_authority(Origin::root(), oracle(6)),
+			Error::<Test>::TooManyOracleAuthorities
+		);
+
+		assert_noop!(
+			OcwModule::remove_oracle_authority(Origin::signed(oracle(1)), oracle(4)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_ok!(OcwModule::remove_oracle_authority(Origin::root(), oracle(4)));
+		assert!(!OcwModule::is_oracle_authority(&oracle(4)));
+		assert_has_event!(Event::<Test>::OracleAuthorityRemoved(oracle(4)));
+		assert_noop!(
+			OcwModule::remove_oracle_authority(Origin::root(), oracle(4)),
+			Error::<Test>::NotOracleAuthority
+		);
+	});
+}
+
+#[test]
+fn only_oracle_authorities_can_submit() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(submit_price(oracle(9), 0, price(6, 0)), Error::<Test>::NotOracleAuthority);
+
+		assert_ok!(OcwModule::add_oracle_authority(Origin::root(), oracle(9)));
+		assert_ok!(submit_price(oracle(9), 0, price(6, 0)));
+	});
+}
+
+#[test]
+fn unsigned_price_from_unknown_key_is_rejected() {
+	let (mut ext, offchain_state, pool_state, public) = new_offchain_test_ext();
+	expect_price_requests(&mut offchain_state.write(), ["6.5", "6.5", "6.5"]);
+
+	ext.execute_with(|| {
+		OcwModule::offchain_worker(4);
+		let (price_payload, signature) = pop_price_call(&pool_state);
+		let call = Call::submit_price_unsigned_with_signed_payload { price_payload, signature };
+		assert!(OcwModule::validate_unsigned(TransactionSource::External, &call).is_ok());
+
+		// 签名有效但不在预言机集合中
+		assert_ok!(OcwModule::remove_oracle_authority(Origin::root(), public));
+		assert_eq!(
+			OcwModule::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::BadSigner.into()
+		);
+	});
+}
+
+#[test]
+fn oracle_submission_count_is_tracked() {
+	new_test_ext().execute_with(|| {
+		finalize_round(0, price(6, 0));
+		finalize_round(1, price(6, 0));
+		assert_ok!(submit_price(oracle(1), 2, price(6, 0)));
+		assert_eq!(OcwModule::oracle_submission_count(oracle(1)), 3);
+		assert_eq!(OcwModule::oracle_submission_count(oracle(2)), 2);
+
+		// 移出预言机集合时清除计数
+		assert_ok!(OcwModule::remove_oracle_authority(Origin::root(), oracle(1)));
+		assert_eq!(OcwModule::oracle_submission_count(oracle(1)), 0);
+	});
+}
+
+/**************************Price_provider_test*******************/
+// 第 1 个区块确定价格 6，第 5 个区块确定价格 8，当前为第 11 个区块
+fn setup_price_history() {
+	finalize_round(0, price(6, 0));
+	System::set_block_number(5);
+	finalize_round(1, price(8, 0));
+	System::set_block_number(11);
+}
+
+#[test]
+fn price_provider_reads_finalized_prices() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(<OcwModule as PriceProvider<u64>>::latest_price(), None);
+		assert_eq!(<OcwModule as PriceProvider<u64>>::twap(10), None);
+
+		setup_price_history();
+		assert_eq!(<OcwModule as PriceProvider<u64>>::latest_price(), Some(price(8, 0)));
+		assert_eq!(<OcwModule as PriceProvider<u64>>::price_at(0), None);
+		assert_eq!(<OcwModule as PriceProvider<u64>>::price_at(1), Some(price(6, 0)));
+		assert_eq!(<OcwModule as PriceProvider<u64>>::price_at(4), Some(price(6, 0)));
+		assert_eq!(<OcwModule as PriceProvider<u64>>::price_at(5), Some(price(8, 0)));
+	});
+}
+
+#[test]
+fn twap_weights_prices_by_blocks() {
+	new_test_ext().execute_with(|| {
+		setup_price_history();
+		// 4 个区块价格为 6，6 个区块价格为 8
+		assert_eq!(<OcwModule as PriceProvider<u64>>::twap(10), Some(price(7, 200_000)));
+		// 窗口内只有价格 8
+		assert_eq!(<OcwModule as PriceProvider<u64>>::twap(4), Some(price(8, 0)));
+		// 第一个价格之前没有数据的区块不参与平均
+		assert_eq!(<OcwModule as PriceProvider<u64>>::twap(100), Some(price(7, 200_000)));
+		// 窗口为 0 时取当前有效的价格
+		assert_eq!(<OcwModule as PriceProvider<u64>>::twap(0), Some(price(8, 0)));
+	});
+}
+
+#[test]
+fn consumer_pallet_quotes_with_twap() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			PriceConsumer::quote(Origin::signed(oracle(1)), 3),
+			pallet_price_consumer::Error::<Test>::PriceUnavailable
+		);
+
+		setup_price_history();
+		assert_ok!(PriceConsumer::quote(Origin::signed(oracle(1)), 3));
+		// 3 DOT * 7.2 USD
+		System::assert_has_event(TestEvent::PriceConsumer(pallet_price_consumer::Event::Quoted(
+			oracle(1),
+			3,
+			price(21, 600_000),
+		)));
+	});
+}
+
+/**************************Price_statistics_test*******************/
+#[test]
+fn running_stats_track_min_max_and_std_dev() {
+	let mut stats = RunningStats::default();
+	assert_eq!(stats.mean(), None);
+	assert_eq!(stats.std_dev(), None);
+
+	let history: Vec<_> = [2, 4, 4, 4, 5, 5, 7, 9].iter().map(|p| price(*p, 0)).collect();
+	history.iter().for_each(|p| stats.insert(*p));
+	assert_eq!((stats.count, stats.min, stats.max), (8, price(2, 0), price(9, 0)));
+	assert_eq!(stats.mean(), Some(price(5, 0)));
+	assert_eq!(stats.std_dev(), Some(price(2, 0)));
+
+	// 淘汰最小值后重新确定最小值
+	stats.remove(history[0], history[1..].iter().copied());
+	assert_eq!((stats.count, stats.min, stats.max), (7, price(4, 0), price(9, 0)));
+	assert_eq!(stats.sum, price(38, 0));
+	// 全部淘汰后归零
+	for (i, p) in history[1..].iter().enumerate() {
+		stats.remove(*p, history[i + 2..].iter().copied());
+	}
+	assert_eq!(stats, RunningStats::default());
+}
+
+#[test]
+fn sqrt_rounds_down() {
+	assert_eq!(price::sqrt(price(4, 0)), price(2, 0));
+	assert_eq!(price::sqrt(price(2, 0)), Price::from_inner(1_414_213_562_373_095_048));
+	assert_eq!(price::sqrt(Price::from_inner(0)), Price::from_inner(0));
+	// 超大的值降低精度计算
+	assert_eq!(price::sqrt(price(1_000_000_000_000, 0)), price(1_000_000, 0));
+}
+
+#[test]
+fn price_statistics_include_twap_windows() {
+	new_test_ext().execute_with(|| {
+		assert_eq!(OcwModule::price_statistics(), None);
+
+		setup_price_history();
+		assert_eq!(
+			OcwModule::price_statistics(),
+			Some(PriceStatistics {
+				latest: FinalizedPrice { round: 1, block_number: 5, price: price(8, 0) },
+				count: 2,
+				min: price(6, 0),
+				max: price(8, 0),
+				mean: price(7, 0),
+				std_dev: price(1, 0),
+				// 窗口 4 内只有价格 8，窗口 10 内 4 个区块价格为 6、6 个区块价格为 8
+				twaps: vec![(4, price(8, 0)), (10, price(7, 200_000))],
+			})
+		);
+	});
+}
+
+/**************************Task_schedule_test*******************/
+fn schedule(period: u64, offset: u64, enabled: bool) -> TaskSchedule<u64> {
+	TaskSchedule { period, offset, enabled }
+}
+
+#[test]
+fn default_schedule_rotates_tasks_every_five_blocks() {
+	new_test_ext().execute_with(|| {
+		for (offset, task) in OffchainTask::ALL.into_iter().enumerate() {
+			assert_eq!(OcwModule::task_schedules(task), Some(schedule(5, offset as u64, true)));
+		}
+	});
+
+	assert!(schedule(5, 4, true).is_due(4));
+	assert!(schedule(5, 4, true).is_due(9));
+	assert!(!schedule(5, 4, true).is_due(0));
+	assert!(!schedule(5, 4, true).is_due(5));
+	assert!(!schedule(5, 4, false).is_due(4));
+	assert!(!schedule(0, 0, true).is_due(0));
+}
+
+#[test]
+fn root_sets_task_schedule() {
+	new_test_ext().execute_with(|| {
+		let task = OffchainTask::FetchPrice;
+		assert_noop!(
+			OcwModule::set_task_schedule(Origin::signed(oracle(1)), task, schedule(2, 1, true)),
+			sp_runtime::DispatchError::BadOrigin
+		);
+		assert_noop!(
+			OcwModule::set_task_schedule(Origin::root(), task, schedule(0, 1, true)),
+			Error::<Test>::InvalidSchedule
+		);
+
+		assert_ok!(OcwModule::set_task_schedule(Origin::root(), task, schedule(2, 1, true)));
+		assert_eq!(OcwModule::task_schedules(task), Some(schedule(2, 1, true)));
+		assert_has_event!(Event::<Test>::TaskScheduleUpdated(task, schedule(2, 1, true)));
+	});
+}
+
+#[test]
+fn offchain_worker_follows_task_schedule() {
+	let (mut ext, offchain_state, pool_state, _) = new_offchain_test_ext();
+	expect_price_requests(&mut offchain_state.write(), ["6.5", "6.5", "6.5"]);
+
+	ext.execute_with(|| {
+		// 只保留价格任务，改为每 3 个区块执行一次
+		for task in OffchainTask::ALL {
+			assert_ok!(OcwModule::set_task_schedule(Origin::root(), task, schedule(5, 0, false)));
+		}
+		let fetch_price = OffchainTask::FetchPrice;
+		assert_ok!(OcwModule::set_task_schedule(Origin::root(), fetch_price, schedule(3, 1, true)));
+
+		// 未到期的区块不会发出请求
+		OcwModule::offchain_worker(8);
+		assert!(pool_state.read().transactions.is_empty());
+		OcwModule::offchain_worker(7);
+		assert_eq!(pool_state.read().transactions.len(), 1);
+
+		assert_ok!(OcwModule::set_task_schedule(
+			Origin::root(),
+			fetch_price,
+			schedule(3, 1, false)
+		));
+		OcwModule::offchain_worker(10);
+		assert_eq!(pool_state.read().transactions.len(), 1);
+	});
+}
+
+#[test]
+fn offchain_tasks_do_not_overlap() {
+	let (mut ext, offchain_state, pool_state, _) = new_offchain_test_ext();
+	expect_price_requests(&mut offchain_state.write(), ["6.5", "6.5", "6.5"]);
+
+	ext.execute_with(|| {
+		// 上一次的任务仍然持有锁时跳过本区块
+		let mut lock = StorageLock::<BlockAndTime<OcwModule>>::new(TASK_LOCK);
+		let guard = lock.try_lock().unwrap();
+		OcwModule::offchain_worker(4);
+		assert!(pool_state.read().transactions.is_empty());
+
+		drop(guard);
+		OcwModule::offchain_worker(4);
+		assert_eq!(pool_state.read().transactions.len(), 1);
+	});
+}
+
+/**************************Migration_test*******************/
+#[test]
+fn migration_converts_prices_to_fixed_point() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<OcwModule>();
+		// v0 中价格编码为 `(u64, Permill)`，没有轮次和区块
+		let old_price = |integer: u64, parts: u32| (integer, Permill::from_parts(parts));
+		frame_support::storage::unhashed::put(
+			&Prices::<Test>::hashed_key(),
+			&vec![old_price(6, 250_000), old_price(6, 500_000)],
+		);
+
+		for task in OffchainTask::ALL {
+			TaskSchedules::<Test>::remove(task);
+		}
+
+		<OcwModule as OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(OcwModule::on_chain_storage_version(), StorageVersion::new(3));
+		assert_eq!(
+			OcwModule::prices(),
+			vec![
+				FinalizedPrice { round: 0, block_number: 0, price: price(6, 250_000) },
+				FinalizedPrice { round: 1, block_number: 0, price: price(6, 500_000) },
+			]
+		);
+		// 新的轮次接在迁移后的价格之后
+		assert_eq!(OcwModule::current_round(), 2);
+
+		// v2 按迁移后的价格初始化统计量
+		assert_eq!(OcwModule::price_stats().count, 2);
+		assert_eq!(OcwModule::price_stats().max, price(6, 500_000));
+		// v3 写入默认的任务计划
+		assert_eq!(
+			OcwModule::task_schedules(OffchainTask::FetchPrice),
+			Some(OffchainTask::FetchPrice.default_schedule())
+		);
+
+		// 已经是最新版本时不会重复迁移
+		<OcwModule as OnRuntimeUpgrade>::on_runtime_upgrade();
+		assert_eq!(OcwModule::prices()[1].price, price(6, 500_000));
+		assert_eq!(OcwModule::price_stats().count, 2);
+	});
+}
+
+#[test]
+fn migration_keeps_version_when_prices_fail_to_decode() {
+	new_test_ext().execute_with(|| {
+		StorageVersion::new(0).put::<OcwModule>();
+		frame_support::storage::unhashed::put_raw(&Prices::<Test>::hashed_key(), &[0xff]);
+
+		<OcwModule as OnRuntimeUpgrade>::on_runtime_upgrade();
+		// 存储版本不变，后续迁移也不会执行
+		assert_eq!(OcwModule::on_chain_storage_version(), StorageVersion::new(0));
+		assert_eq!(
+			frame_support::storage::unhashed::get_raw(&Prices::<Test>::hashed_key()),
+			Some(vec![0xff])
+		);
+	});
+}
+
+#[test]
+fn submit_price_requires_none_origin() {
+	let (mut ext, _, _, public) = new_offchain_test_ext();
+	ext.execute_with(|| {
+		let signature = sr25519::Signature::from_raw([0u8; 64]);
+		let payload = PricePayload {
+			block_number: 1,
+			round: 0,
+			parsed_price: price(1, 0),
+			sources: vec![],
+			public,
+		};
+		assert_noop!(
+			OcwModule::submit_price_unsigned_with_signed_payload(
+				Origin::signed(public),
+				payload,
+				signature
+			),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	});
+}
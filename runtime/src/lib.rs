@@ -6,6 +6,7 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
+use codec::Encode;
 use pallet_grandpa::{
 	fg_primitives, AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList,
 };
@@ -16,7 +17,7 @@ use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
 	traits::{AccountIdLookup, BlakeTwo256, Block as BlockT, IdentifyAccount, NumberFor, Verify},
 	transaction_validity::{TransactionSource, TransactionValidity},
-	ApplyExtrinsicResult, MultiSignature,
+	ApplyExtrinsicResult, MultiSignature, SaturatedConversion,
 };
 use sp_std::prelude::*;
 #[cfg(feature = "std")]
@@ -44,6 +45,7 @@ pub use sp_runtime::BuildStorage;
 pub use sp_runtime::{Perbill, Permill};
 
 pub use pallet_kitties;
+pub use pallet_ocw;
 pub use pallet_poe;
 /// Import the template pallet.
 pub use pallet_template;
@@ -66,6 +68,12 @@ pub type Index = u32;
 /// A hash of some data used by the chain.
 pub type Hash = sp_core::H256;
 
+/// Index of a kitty.
+pub type KittyIndex = u32;
+
+/// Identifier of a fungible asset.
+pub type AssetId = u32;
+
 /// Opaque types. These are used by the CLI to instantiate machinery that don't need to know
 /// the specifics of the runtime. They can then be made to be agnostic over specific formats
 /// of data like extrinsics, allowing for them to continue syncing the network through upgrades
@@ -252,6 +260,31 @@ impl pallet_balances::Config for Runtime {
 	type WeightInfo = pallet_balances::weights::SubstrateWeight<Runtime>;
 }
 
+parameter_types! {
+	pub const AssetDeposit: Balance = 100 * EXISTENTIAL_DEPOSIT;
+	pub const AssetAccountDeposit: Balance = EXISTENTIAL_DEPOSIT;
+	pub const AssetMetadataDepositBase: Balance = 10 * EXISTENTIAL_DEPOSIT;
+	pub const AssetMetadataDepositPerByte: Balance = EXISTENTIAL_DEPOSIT / 10;
+	pub const AssetApprovalDeposit: Balance = EXISTENTIAL_DEPOSIT;
+}
+
+impl pallet_assets::Config for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type Currency = Balances;
+	type ForceOrigin = frame_system::EnsureRoot<AccountId>;
+	type AssetDeposit = AssetDeposit;
+	type AssetAccountDeposit = AssetAccountDeposit;
+	type MetadataDepositBase = AssetMetadataDepositBase;
+	type MetadataDepositPerByte = AssetMetadataDepositPerByte;
+	type ApprovalDeposit = AssetApprovalDeposit;
+	type StringLimit = ConstU32<50>;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+}
+
 impl pallet_transaction_payment::Config for Runtime {
 	type Event = Event;
 	type OnChargeTransaction = CurrencyAdapter<Balances, ()>;
@@ -279,16 +312,64 @@ impl pallet_poe::Config for Runtime {
 parameter_types! {
 	pub const KittyStake: u128 = 1_000;
 	pub const MaxKittyIndex: u32 = 5;
+	pub const KittyRevealDelay: BlockNumber = 2;
+	pub const KittyBreedingLockPeriod: BlockNumber = 10 * MINUTES;
+	pub const KittyMetadataDepositPerByte: Balance = 10;
+	pub const KittyRoyaltyRate: Permill = Permill::from_percent(5);
 }
 
 /// 为Runtime实现pallet_kitties配置接口
 impl pallet_kitties::Config for Runtime {
 	type Event = Event;
 	type Randomness = RandomnessCollectiveFlip;
-	type KittyIndex = u32;
+	type KittyIndex = KittyIndex;
 	type KittyStake = KittyStake;
 	type Currency = Balances;
 	type MaxKittyIndex = MaxKittyIndex;
+	// 测试链可以换成 `pallet_kitties::DominantGeneMixer<...>` 等其他混合策略
+	type DnaMixer = ();
+	type RevealDelay = KittyRevealDelay;
+	type BreedingLockPeriod = KittyBreedingLockPeriod;
+	type MaxNameLength = ConstU32<32>;
+	type MaxMetadataUriLength = ConstU32<256>;
+	type MetadataDepositPerByte = KittyMetadataDepositPerByte;
+	type AuthorityId = pallet_kitties::crypto::KittiesAuthId;
+	type AssetAuthorityOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxAssetAuthorities = ConstU32<32>;
+	type RoyaltyRate = KittyRoyaltyRate;
+	// 游戏内代币等资产由 pallet_assets 发行
+	type Assets = Assets;
+	type MaxTradesPerDeadline = ConstU32<64>;
+	type WeightInfo = pallet_kitties::weights::SubstrateWeight<Runtime>;
+}
+
+parameter_types! {
+	pub const OcwPriceSources: &'static [pallet_ocw::PriceSource] = pallet_ocw::DOT_PRICE_SOURCES;
+	pub const OcwMaxPriceDeviation: Permill = Permill::from_percent(5);
+	pub const OcwMaxPriceHistory: u32 = 100;
+	pub OcwTwapWindows: Vec<BlockNumber> = vec![MINUTES, 10 * MINUTES, 50 * MINUTES];
+	// 最少一个预言机提交，实际法定人数取预言机集合的过半数：
+	// 开发链只有 Alice，local_testnet 需要 Alice 和 Bob 都提交
+	pub const OcwPriceQuorum: u32 = 1;
+	pub const OcwMaxOracleAuthorities: u32 = 32;
+	// 价格任务每 5 个区块执行一次，约四次任务仍凑不齐法定人数就作废本轮
+	pub const OcwRoundTimeout: BlockNumber = 2 * MINUTES;
+}
+
+/// 链下工作机示例及 DOT 价格预言机
+impl pallet_ocw::Config for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type AuthorityId = pallet_ocw::crypto::TestAuthId;
+	type PriceSources = OcwPriceSources;
+	type MaxPriceDeviation = OcwMaxPriceDeviation;
+	type MaxPriceHistory = OcwMaxPriceHistory;
+	type TwapWindows = OcwTwapWindows;
+	type PriceQuorum = OcwPriceQuorum;
+	type ScheduleOrigin = frame_system::EnsureRoot<AccountId>;
+	type OracleOrigin = frame_system::EnsureRoot<AccountId>;
+	type MaxOracleAuthorities = OcwMaxOracleAuthorities;
+	type RoundTimeout = OcwRoundTimeout;
 }
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
@@ -304,12 +385,14 @@ construct_runtime!(
 		Aura: pallet_aura,
 		Grandpa: pallet_grandpa,
 		Balances: pallet_balances,
+		Assets: pallet_assets,
 		TransactionPayment: pallet_transaction_payment,
 		Sudo: pallet_sudo,
 		// Include the custom logic from the pallet-template in the runtime.
 		TemplateModule: pallet_template,
 		PoeModule: pallet_poe,
 		KittiesModule: pallet_kitties,
+		OcwModule: pallet_ocw,
 	}
 );
 
@@ -334,6 +417,52 @@ pub type SignedExtra = (
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
+impl frame_system::offchain::SigningTypes for Runtime {
+	type Public = <Signature as Verify>::Signer;
+	type Signature = Signature;
+}
+
+impl<C> frame_system::offchain::SendTransactionTypes<C> for Runtime
+where
+	Call: From<C>,
+{
+	type OverarchingCall = Call;
+	type Extrinsic = UncheckedExtrinsic;
+}
+
+/// 为链下工作机构造签名交易
+impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
+where
+	Call: From<LocalCall>,
+{
+	fn create_transaction<C: frame_system::offchain::AppCrypto<Self::Public, Self::Signature>>(
+		call: Call,
+		public: <Signature as Verify>::Signer,
+		account: AccountId,
+		nonce: Index,
+	) -> Option<(Call, <UncheckedExtrinsic as sp_runtime::traits::Extrinsic>::SignaturePayload)> {
+		let tip = 0;
+		// 交易有效期取不超过 BlockHashCount 的最大2的幂
+		let period =
+			BlockHashCount::get().checked_next_power_of_two().map(|c| c / 2).unwrap_or(2) as u64;
+		let current_block = System::block_number().saturated_into::<u64>().saturating_sub(1);
+		let extra: SignedExtra = (
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckTxVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(generic::Era::mortal(period, current_block)),
+			frame_system::CheckNonce::<Runtime>::from(nonce),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(tip),
+		);
+		let raw_payload = SignedPayload::new(call, extra).ok()?;
+		let signature = raw_payload.using_encoded(|payload| C::sign(payload, public))?;
+		let (call, extra, _) = raw_payload.deconstruct();
+		Some((call, (sp_runtime::MultiAddress::Id(account), signature, extra)))
+	}
+}
+
 /// Executive: handles dispatch to the various modules.
 pub type Executive = frame_executive::Executive<
 	Runtime,
@@ -353,9 +482,11 @@ mod benches {
 		[frame_benchmarking, BaselineBench::<Runtime>]
 		[frame_system, SystemBench::<Runtime>]
 		[pallet_balances, Balances]
+		[pallet_assets, Assets]
 		[pallet_timestamp, Timestamp]
 		[pallet_template, TemplateModule]
 		[pallet_poe, PoeModule]
+		[pallet_kitties, KittiesModule]
 	);
 }
 
@@ -490,6 +621,42 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_kitties_runtime_api::KittiesApi<Block, AccountId, KittyIndex, Balance, AssetId>
+		for Runtime
+	{
+		fn kitty(
+			kitty_id: KittyIndex,
+		) -> Option<pallet_kitties::KittyInfo<AccountId, Balance, AssetId>> {
+			KittiesModule::kitty_info(kitty_id)
+		}
+
+		fn kitties_for_sale(page: u32) -> Vec<(KittyIndex, Balance, Option<AssetId>)> {
+			KittiesModule::kitties_for_sale(page)
+		}
+
+		fn price_of(kitty_id: KittyIndex) -> Option<(Balance, Option<AssetId>)> {
+			KittiesModule::price_of(kitty_id)
+		}
+
+		fn ancestors(kitty_id: KittyIndex, depth: u32) -> Vec<KittyIndex> {
+			KittiesModule::ancestors(kitty_id, depth)
+		}
+
+		fn descendants(kitty_id: KittyIndex, page: u32) -> Vec<KittyIndex> {
+			KittiesModule::descendants(kitty_id, page)
+		}
+	}
+
+	impl pallet_ocw_runtime_api::OcwApi<Block, BlockNumber> for Runtime {
+		fn latest_price() -> Option<pallet_ocw::FinalizedPrice<BlockNumber>> {
+			OcwModule::prices().back().copied()
+		}
+
+		fn price_statistics() -> Option<pallet_ocw::PriceStatistics<BlockNumber>> {
+			OcwModule::price_statistics()
+		}
+	}
+
 	#[cfg(feature = "runtime-benchmarks")]
 	impl frame_benchmarking::Benchmark<Block> for Runtime {
 		fn benchmark_metadata(extra: bool) -> (